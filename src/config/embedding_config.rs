@@ -0,0 +1,39 @@
+use crate::services::embedding_service::EmbeddingService;
+use log::error;
+use std::env;
+use std::time::Duration;
+
+/// Creates the embedding client when `EMBEDDING_URL` is set; ingest-time embeddings are
+/// disabled otherwise. Requests give up after `EMBEDDING_TIMEOUT_SECS` (30 by default) so a
+/// hung endpoint can't hold `/add` forever.
+pub fn create_embedding_service() -> Option<EmbeddingService> {
+    let url = env::var("EMBEDDING_URL")
+        .ok()
+        .filter(|url| !url.is_empty())?;
+    let model = env::var("EMBEDDING_MODEL").ok();
+    let api_key = env::var("EMBEDDING_API_KEY").ok();
+    let batch_size = env::var("EMBEDDING_BATCH_SIZE")
+        .ok()
+        .and_then(|size| size.parse::<usize>().ok())
+        .filter(|size| *size > 0)
+        .unwrap_or(32);
+    let timeout = env::var("EMBEDDING_TIMEOUT_SECS")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(30);
+
+    match EmbeddingService::new(
+        url,
+        model,
+        api_key,
+        batch_size,
+        Duration::from_secs(timeout),
+    ) {
+        Ok(service) => Some(service),
+        Err(e) => {
+            error!("Could not create the embedding client: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::models::index_request::IndexRequest;
+use log::{error, info};
+use std::env;
+use std::error::Error;
+use std::path::Path;
+
+const DEFINITION_EXTENSIONS: [&str; 4] = ["yaml", "yml", "toml", "json"];
+
+/// Loads the index definitions shipped with the deployment, one `IndexRequest` per YAML,
+/// TOML or JSON file in `INDEX_DEFINITIONS_DIR` (default `indexes`). Files that fail to
+/// parse are logged and skipped.
+pub fn load_index_definitions() -> Vec<IndexRequest> {
+    let dir = env::var("INDEX_DEFINITIONS_DIR").unwrap_or_else(|_| "indexes".to_string());
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            info!("No index definitions loaded from '{}': {}", dir, e);
+            return Vec::new();
+        }
+    };
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| DEFINITION_EXTENSIONS.contains(&extension))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| match load_index_definition(path) {
+            Ok(definition) => Some(definition),
+            Err(e) => {
+                error!("Invalid index definition '{}': {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parses a definition file by its extension. The files are read with the format parsers
+/// directly because layered config loaders lowercase keys, such as dictionary names.
+fn load_index_definition(path: &Path) -> Result<IndexRequest, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let definition = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => toml::from_str(&contents)?,
+        Some("json") => serde_json::from_str(&contents)?,
+        _ => serde_yaml::from_str(&contents)?,
+    };
+    Ok(definition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(name: &str, contents: &str) -> IndexRequest {
+        let dir = env::temp_dir().join(format!("index-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        let definition = load_index_definition(&path);
+        std::fs::remove_file(&path).unwrap();
+        definition.unwrap()
+    }
+
+    #[test]
+    fn shipped_definitions_parse() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("indexes/espn.yaml");
+        let definition = load_index_definition(&path).unwrap();
+        assert_eq!(definition.index_name, "myIndex");
+        assert!(definition.schema.iter().any(|field| field.required));
+    }
+
+    #[test]
+    fn yaml_keys_keep_their_case() {
+        let definition = load(
+            "posts.yaml",
+            r#"
+index_name: MyIndex
+type: JSON
+prefixes: ["Post:"]
+schema:
+  - field_name: "$.postTitle as postTitle"
+    field_type: TEXT
+dictionaries:
+  BrandNames: [Nike, adidas]
+"#,
+        );
+        assert_eq!(definition.index_name, "MyIndex");
+        assert_eq!(definition.prefixes, ["Post:"]);
+        assert_eq!(definition.schema[0].field_name, "$.postTitle as postTitle");
+        let dictionaries = definition.dictionaries.unwrap();
+        assert_eq!(dictionaries.keys().collect::<Vec<_>>(), ["BrandNames"]);
+        assert_eq!(dictionaries["BrandNames"], ["Nike", "adidas"]);
+    }
+
+    #[test]
+    fn toml_keys_keep_their_case() {
+        let definition = load(
+            "posts.toml",
+            r#"
+index_name = "MyIndex"
+type = "HASH"
+prefixes = ["Post:"]
+
+[[schema]]
+field_name = "postTitle"
+field_type = "TEXT"
+
+[dictionaries]
+BrandNames = ["Nike"]
+"#,
+        );
+        assert_eq!(definition.schema[0].field_name, "postTitle");
+        assert!(definition.dictionaries.unwrap().contains_key("BrandNames"));
+    }
+}
@@ -1 +1,3 @@
+pub mod embedding_config;
+pub mod index_config;
 pub mod redis_config;
@@ -4,10 +4,16 @@ mod routes;
 mod services;
 mod utils;
 
+use crate::config::embedding_config::create_embedding_service;
+use crate::config::index_config::load_index_definitions;
 use crate::config::redis_config::create_redis_pool;
+use crate::models::index_request::IndexRequest;
+use crate::models::registry_request::RestoreRequest;
 use crate::services::redis_service::RedisService;
 use actix_web::middleware::Compress;
 use actix_web::{web, App, HttpServer};
+use log::{error, info};
+use std::time::Duration;
 
 pub struct AppState {
     pub redis_service: RedisService,
@@ -20,9 +26,15 @@ async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
 
     let redis_pool = create_redis_pool().await;
-    let redis_service = RedisService::new(redis_pool);
+    let embedding_service = create_embedding_service();
+    let redis_service = RedisService::new(redis_pool, embedding_service);
     let app_data = web::Data::new(AppState { redis_service });
 
+    // Declared indexes are created or migrated in the background, so a rebuild never delays
+    // startup; the alias keeps serving the previous version meanwhile
+    let definitions = load_index_definitions();
+    actix_web::rt::spawn(apply_index_definitions(app_data.clone(), definitions));
+
     // Load IP address and port from environment variables
     let server_ip = std::env::var("SERVER_IP").unwrap_or_else(|_| "0.0.0.0".to_string());
     let server_port = std::env::var("SERVER_PORT").unwrap_or_else(|_| "8080".to_string());
@@ -38,3 +50,35 @@ async fn main() -> std::io::Result<()> {
     .run()
     .await
 }
+
+/// Migrates the index registry, then applies the declared index definitions through the
+/// same path as `/index` once Redis answers; failures of single definitions are reported
+/// in the log.
+async fn apply_index_definitions(app_data: web::Data<AppState>, definitions: Vec<IndexRequest>) {
+    for attempt in 1..=10 {
+        if let Err(e) = app_data.redis_service.status().await {
+            error!("Redis is not ready (attempt {}): {}", attempt, e);
+            actix_web::rt::time::sleep(Duration::from_secs(3)).await;
+            continue;
+        }
+
+        match app_data.redis_service.migrate_registry().await {
+            Ok(0) => {}
+            Ok(migrated) => info!("Migrated {} registry entries", migrated),
+            Err(e) => error!("Failed to migrate the index registry: {}", e),
+        }
+        if definitions.is_empty() {
+            return;
+        }
+
+        let request = RestoreRequest {
+            definitions: Some(definitions),
+        };
+        match app_data.redis_service.restore_registry(request).await {
+            Ok(result) => info!("Applied index definitions: {}", result["data"]),
+            Err(e) => error!("Failed to apply index definitions: {}", e),
+        }
+        return;
+    }
+    error!("Index definitions were not applied, Redis never became ready");
+}
@@ -0,0 +1,44 @@
+use crate::models::filter::{Filter, RangeFilter, TagFilter};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AggregateRequest {
+    pub index: Option<String>,
+    pub q: Option<String>,
+    pub filter: Option<Filter>,
+    pub numeric_filters: Option<Vec<RangeFilter>>,
+    pub tag_filters: Option<Vec<TagFilter>>,
+
+    /// Extra attributes to LOAD; fields named in `group_by`, `reducers` and `apply` are
+    /// loaded automatically.
+    pub load: Option<Vec<String>>,
+    pub group_by: Option<Vec<String>>,
+    pub reducers: Option<Vec<Reducer>>,
+    /// APPLY steps evaluated on each document before grouping.
+    pub apply: Option<Vec<ApplyStep>>,
+    /// FILTER expressions evaluated on the buckets after grouping.
+    pub filters: Option<Vec<String>>,
+    pub sort_by: Option<Vec<SortKey>>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reducer {
+    pub function: String,
+    pub field: Option<String>,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyStep {
+    pub expression: String,
+    pub alias: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortKey {
+    pub field: String,
+    pub order: Option<String>,
+}
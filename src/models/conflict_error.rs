@@ -0,0 +1,20 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error for a request that clashes with an operation in progress, reported as 409 by the routes.
+#[derive(Debug)]
+pub struct ConflictError(pub String);
+
+impl ConflictError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ConflictError(message.into())
+    }
+}
+
+impl fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ConflictError {}
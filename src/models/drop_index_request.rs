@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DropIndexQuery {
+    /// Must repeat the index name for the drop to go through.
+    pub confirm: Option<String>,
+    /// Also delete the indexed documents (FT.DROPINDEX DD).
+    pub delete_documents: Option<bool>,
+}
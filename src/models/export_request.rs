@@ -0,0 +1,14 @@
+use crate::models::search_request::SearchRequest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportRequest {
+    /// Query, filters and projection, as accepted by `/search`. `limit` is the page size
+    /// used to read from Redis.
+    #[serde(flatten)]
+    pub search: SearchRequest,
+    /// `ndjson` (default) or `csv`.
+    pub format: Option<String>,
+    /// Columns written to CSV exports, in order.
+    pub columns: Option<Vec<String>>,
+}
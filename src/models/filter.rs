@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+/// Structured search filter compiled into a RediSearch query string.
+///
+/// Serialized as an externally tagged object, e.g.
+/// `{"and": [{"term": {"field": "post_title", "value": "lebron"}}, {"tag_in": {...}}]}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+    Term(TermFilter),
+    Phrase(TermFilter),
+    Prefix(TermFilter),
+    Range(RangeFilter),
+    TagIn(TagFilter),
+    GeoRadius(GeoRadiusFilter),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TermFilter {
+    /// Omit to match against every TEXT field of the index.
+    pub field: Option<String>,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeFilter {
+    pub field: String,
+    pub gt: Option<f64>,
+    pub gte: Option<f64>,
+    pub lt: Option<f64>,
+    pub lte: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagFilter {
+    pub field: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoRadiusFilter {
+    pub field: String,
+    pub lon: f64,
+    pub lat: f64,
+    pub radius: f64,
+    pub unit: Option<String>,
+}
+
+/// Geo filter on a GEO (radius) or GEOSHAPE (polygon) field, e.g.
+/// `{"polygon": {"field": "area", "points": [[2.29, 48.85], [2.30, 48.86], [2.31, 48.85]]}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoFilter {
+    Radius(GeoRadiusFilter),
+    Polygon(GeoPolygonFilter),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoPolygonFilter {
+    pub field: String,
+    /// `[lon, lat]` (or `[x, y]` for FLAT fields) vertices; the ring is closed automatically.
+    pub points: Vec<[f64; 2]>,
+    /// WITHIN (default), CONTAINS, INTERSECTS or DISJOINT.
+    pub relation: Option<String>,
+}
@@ -0,0 +1,20 @@
+use crate::models::filter::{Filter, RangeFilter, TagFilter};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistogramRequest {
+    pub index: Option<String>,
+    pub q: Option<String>,
+    pub filter: Option<Filter>,
+    pub numeric_filters: Option<Vec<RangeFilter>>,
+    pub tag_filters: Option<Vec<TagFilter>>,
+    pub language: Option<String>,
+
+    /// Numeric timestamp field to bucket on, defaults to `post_timestamp`.
+    pub field: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub interval: Option<String>,
+    /// Timezone offset in whole hours used for the bounds and calendar buckets, defaults to 8.
+    pub timezone: Option<i32>,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Progress of the last index build of an alias, reported by `GET /index/{name}/build`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexBuildStatus {
+    pub build_id: String,
+    pub index_name: String,
+    /// `running`, `success` or `failed`.
+    pub status: String,
+    pub plan: Value,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// The `/index` response, once the build succeeded.
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
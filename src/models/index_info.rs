@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// FT.INFO of an index, parsed into a keyed structure.
+#[derive(Debug, Default, Serialize)]
+pub struct IndexInfo {
+    pub index_name: String,
+    pub index_options: Vec<String>,
+    pub index_definition: IndexDefinitionInfo,
+    pub attributes: Vec<AttributeInfo>,
+    pub num_docs: u64,
+    pub max_doc_id: u64,
+    pub num_terms: u64,
+    pub num_records: u64,
+    pub indexing: bool,
+    /// Between 0 and 1.
+    pub percent_indexed: f64,
+    pub hash_indexing_failures: u64,
+    pub number_of_uses: u64,
+    pub memory: MemoryStats,
+    pub gc_stats: GcStats,
+    pub cursor_stats: CursorStats,
+    pub index_errors: IndexErrors,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IndexDefinitionInfo {
+    pub key_type: String,
+    pub prefixes: Vec<String>,
+    pub default_score: Option<f64>,
+    /// Other definition settings such as `filter` or `language_field`.
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub options: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AttributeInfo {
+    pub identifier: String,
+    pub attribute: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    pub weight: Option<f64>,
+    pub separator: Option<String>,
+    /// SORTABLE, UNF, NOSTEM, NOINDEX, CASESENSITIVE, WITHSUFFIXTRIE, INDEXEMPTY, INDEXMISSING.
+    pub flags: Vec<String>,
+    /// Other type-specific settings, e.g. vector parameters.
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub options: Map<String, Value>,
+}
+
+impl AttributeInfo {
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryStats {
+    pub inverted_sz_mb: Option<f64>,
+    pub vector_index_sz_mb: Option<f64>,
+    pub total_inverted_index_blocks: Option<f64>,
+    pub offset_vectors_sz_mb: Option<f64>,
+    pub doc_table_size_mb: Option<f64>,
+    pub sortable_values_size_mb: Option<f64>,
+    pub key_table_size_mb: Option<f64>,
+    pub geoshapes_sz_mb: Option<f64>,
+    pub tag_overhead_sz_mb: Option<f64>,
+    pub text_overhead_sz_mb: Option<f64>,
+    pub total_index_memory_sz_mb: Option<f64>,
+    pub records_per_doc_avg: Option<f64>,
+    pub bytes_per_record_avg: Option<f64>,
+    pub offsets_per_term_avg: Option<f64>,
+    pub offset_bits_per_record_avg: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GcStats {
+    pub bytes_collected: Option<f64>,
+    pub total_ms_run: Option<f64>,
+    pub total_cycles: Option<f64>,
+    pub average_cycle_time_ms: Option<f64>,
+    pub last_run_time_ms: Option<f64>,
+    pub gc_numeric_trees_missed: Option<f64>,
+    pub gc_blocks_denied: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CursorStats {
+    pub global_idle: Option<f64>,
+    pub global_total: Option<f64>,
+    pub index_capacity: Option<f64>,
+    pub index_total: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexErrors {
+    #[serde(alias = "indexing failures")]
+    pub indexing_failures: Option<f64>,
+    #[serde(alias = "last indexing error")]
+    pub last_indexing_error: Option<String>,
+    #[serde(alias = "last indexing error key")]
+    pub last_indexing_error_key: Option<String>,
+}
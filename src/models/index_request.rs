@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexRequest {
+    pub index_name: String,
+    #[serde(rename = "type")]
+    pub index_type: String,
+    pub language: Option<String>,
+    pub prefixes: Vec<String>,
+    pub schema: Vec<SchemaField>,
+    /// Fields feeding the autocomplete dictionaries of the index.
+    pub suggest: Option<Vec<SuggestSource>>,
+
+    /// Custom stopword list; an empty list disables stopwords.
+    pub stopwords: Option<Vec<String>>,
+    /// Only index documents matching this aggregation expression.
+    pub filter: Option<String>,
+    pub language_field: Option<String>,
+    /// Default document score, between 0 and 1.
+    pub score: Option<f64>,
+    pub score_field: Option<String>,
+    pub payload_field: Option<String>,
+    #[serde(default)]
+    pub max_text_fields: bool,
+    /// Seconds of inactivity after which the index is dropped.
+    pub temporary: Option<u64>,
+    #[serde(default)]
+    pub no_offsets: bool,
+    #[serde(default)]
+    pub no_highlight: bool,
+    #[serde(default)]
+    pub no_fields: bool,
+    #[serde(default)]
+    pub no_freqs: bool,
+    #[serde(default)]
+    pub skip_initial_scan: bool,
+    /// Custom dictionaries (e.g. for spellchecking) filled with FT.DICTADD, by name.
+    pub dictionaries: Option<BTreeMap<String, Vec<String>>>,
+
+    /// Only report the plan `/index` would apply.
+    #[serde(default, skip_serializing)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaField {
+    /// Attribute name, or `<JSONPath> as <attribute>` for JSON indexes.
+    pub field_name: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub sortable: bool,
+    /// Required when `field_type` is VECTOR.
+    pub vector: Option<VectorOptions>,
+    /// GEOSHAPE only: SPHERICAL (default, lon/lat) or FLAT (cartesian x/y).
+    pub coord_system: Option<String>,
+
+    /// TEXT only: importance of the field when scoring, defaults to 1.
+    pub weight: Option<f64>,
+    /// TEXT only.
+    #[serde(default)]
+    pub nostem: bool,
+    /// TEXT only: dm:en, dm:fr, dm:pt or dm:es.
+    pub phonetic: Option<String>,
+    /// TAG only: defaults to `,`.
+    pub separator: Option<String>,
+    /// TAG only.
+    #[serde(default)]
+    pub casesensitive: bool,
+    /// TEXT and TAG only: speeds up suffix and contains queries.
+    #[serde(default)]
+    pub withsuffixtrie: bool,
+    /// TEXT and TAG only.
+    #[serde(default)]
+    pub index_empty: bool,
+    #[serde(default)]
+    pub index_missing: bool,
+    /// Keep sortable values unnormalized (requires `sortable`).
+    #[serde(default)]
+    pub unf: bool,
+    /// Store the field for sorting or returning without indexing it.
+    #[serde(default)]
+    pub noindex: bool,
+    /// Not part of FT.CREATE: `/add` rejects documents under the index prefixes that lack
+    /// this field.
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorOptions {
+    /// FLAT or HNSW.
+    pub algorithm: String,
+    pub dim: u32,
+    /// L2, IP or COSINE.
+    pub distance_metric: String,
+    /// FLOAT32 (default) or FLOAT64.
+    pub data_type: Option<String>,
+    pub initial_cap: Option<u32>,
+    /// FLAT only.
+    pub block_size: Option<u32>,
+    /// HNSW only.
+    pub m: Option<u32>,
+    pub ef_construction: Option<u32>,
+    pub ef_runtime: Option<u32>,
+    /// Text fields embedded into this vector by the configured embedding backend when
+    /// documents are added without it.
+    pub source_fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestSource {
+    /// JSONPath of a string or string array field, e.g. `$.post_title`.
+    pub field: String,
+    /// Dictionary name, defaults to the last segment of `field`.
+    pub dictionary: Option<String>,
+    /// Numeric field used as the suggestion score instead of the number of documents.
+    pub score_field: Option<String>,
+    /// Field stored as the suggestion payload, e.g. a document URL.
+    pub payload_field: Option<String>,
+}
+
+/// A definition accepted by `/index`, as kept in the index registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexRegistryEntry {
+    pub definition: IndexRequest,
+    /// Incremented every time `/index` accepts a changed definition.
+    pub revision: u64,
+    /// Version of the physical index `<index_name>_v<N>` the alias points at.
+    pub index_version: Option<u32>,
+    pub created_at: String,
+    pub updated_at: String,
+}
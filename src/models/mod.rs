@@ -1,2 +1,16 @@
+pub mod aggregate_request;
+pub mod conflict_error;
 pub mod delete_request;
+pub mod drop_index_request;
+pub mod export_request;
+pub mod filter;
+pub mod histogram_request;
+pub mod index_build;
+pub mod index_info;
+pub mod index_request;
+pub mod not_found_error;
+pub mod registry_request;
 pub mod search_request;
+pub mod suggest_request;
+pub mod synonym_request;
+pub mod validation_error;
@@ -0,0 +1,20 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error for a resource that does not exist, reported as 404 by the routes.
+#[derive(Debug)]
+pub struct NotFoundError(pub String);
+
+impl NotFoundError {
+    pub fn new(message: impl Into<String>) -> Self {
+        NotFoundError(message.into())
+    }
+}
+
+impl fmt::Display for NotFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for NotFoundError {}
@@ -0,0 +1,15 @@
+use crate::models::index_request::IndexRequest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RestoreRequest {
+    /// Definitions to apply, e.g. a saved `GET /registry` listing. Defaults to the registered
+    /// definitions whose index is missing.
+    pub definitions: Option<Vec<IndexRequest>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistryDeleteQuery {
+    /// No longer supported: indexes are dropped with a confirmed `DELETE /indexes/{name}`.
+    pub drop_index: Option<bool>,
+}
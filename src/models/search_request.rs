@@ -1,9 +1,15 @@
+use crate::models::aggregate_request::SortKey;
+use crate::models::filter::{Filter, GeoFilter, RangeFilter, TagFilter};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SearchRequest {
     pub index: Option<String>,
     pub q: Option<String>,
+    pub filter: Option<Filter>,
+    pub numeric_filters: Option<Vec<RangeFilter>>,
+    pub tag_filters: Option<Vec<TagFilter>>,
+    pub geo_filters: Option<Vec<GeoFilter>>,
     pub offset: Option<i64>,
     pub limit: Option<i64>,
     pub start_time: Option<String>,
@@ -13,4 +19,101 @@ pub struct SearchRequest {
     pub filter_date_by: Option<String>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
+    /// Several sort keys, applied in order. Takes precedence over `sort_by`/`sort_order`.
+    pub sort: Option<Vec<SortKey>>,
+    /// Orders hits by distance from a point and returns it as `_geo_distance`. Any `sort`
+    /// keys break ties.
+    pub geo_sort: Option<GeoSort>,
+
+    /// Vector similarity query. The other filters of the request act as a prefilter.
+    pub knn: Option<KnnQuery>,
+    /// Combines `q` with `knn` and fuses both rankings.
+    pub hybrid: Option<HybridOptions>,
+    /// TFIDF, TFIDF.DOCNORM, BM25, BM25STD, DISMAX, DOCSCORE or HAMMING.
+    pub scorer: Option<String>,
+    pub explain_score: Option<bool>,
+    pub return_fields: Option<Vec<ReturnField>>,
+
+    /// Page through the results with a server-side cursor instead of LIMIT offsets.
+    pub scroll: Option<bool>,
+    /// Continuation token returned by the previous scroll page.
+    pub cursor: Option<String>,
+    /// How long an unread cursor is kept alive, defaults to 300000.
+    pub max_idle_ms: Option<u64>,
+    pub highlight: Option<HighlightOptions>,
+    pub summarize: Option<SummarizeOptions>,
+    /// Suggests corrections for misspelled terms of `q`.
+    pub spellcheck: Option<SpellcheckOptions>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoSort {
+    /// GEO field holding the document location.
+    pub field: String,
+    pub lon: f64,
+    pub lat: f64,
+    /// m, km (default), mi or ft.
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HighlightOptions {
+    /// Defaults to `post_title` and `post_message`.
+    pub fields: Option<Vec<String>>,
+    pub open_tag: Option<String>,
+    pub close_tag: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummarizeOptions {
+    /// Defaults to `post_message`.
+    pub fields: Option<Vec<String>>,
+    pub frags: Option<u32>,
+    pub len: Option<u32>,
+    pub separator: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReturnField {
+    /// JSONPath such as `$.post_title`, or an index attribute name.
+    pub path: String,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnnQuery {
+    /// VECTOR attribute to search.
+    pub field: String,
+    pub vector: Vec<f64>,
+    /// Number of nearest neighbours, defaults to `offset + limit`.
+    pub k: Option<i64>,
+    pub ef_runtime: Option<u32>,
+    /// Must match the TYPE of the VECTOR field, FLOAT32 (default) or FLOAT64.
+    pub data_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HybridOptions {
+    /// `rrf` (reciprocal rank fusion, default) or `weighted`.
+    pub method: Option<String>,
+    /// RRF rank constant, defaults to 60.
+    pub rrf_k: Option<f64>,
+    /// Weights of the min-max normalized leg scores, both default to 0.5.
+    pub text_weight: Option<f64>,
+    pub vector_weight: Option<f64>,
+    /// Candidates fetched from each leg, defaults to `max(offset + limit, 50)`.
+    pub window: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpellcheckOptions {
+    /// Maximum Levenshtein distance of the suggestions, 1 (default) to 4.
+    pub distance: Option<u32>,
+    /// Custom dictionaries whose terms are suggested too.
+    pub include: Option<Vec<String>>,
+    /// Custom dictionaries whose terms are never reported as misspelled.
+    pub exclude: Option<Vec<String>>,
+    /// Also check queries that returned hits; by default only empty results are checked.
+    #[serde(default)]
+    pub always: bool,
 }
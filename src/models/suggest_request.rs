@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestRequest {
+    pub index: String,
+    pub dictionary: String,
+    pub prefix: String,
+    /// Also match prefixes one edit (Levenshtein distance) away.
+    pub fuzzy: Option<bool>,
+    /// Defaults to 5.
+    pub max: Option<u32>,
+    pub with_scores: Option<bool>,
+    pub with_payloads: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestionAddRequest {
+    pub index: String,
+    pub dictionary: String,
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub string: String,
+    /// Defaults to 1.
+    pub score: Option<f64>,
+    pub payload: Option<String>,
+    /// Adds `score` to the existing score instead of replacing it.
+    pub incr: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestionDeleteRequest {
+    pub index: String,
+    pub dictionary: String,
+    pub strings: Vec<String>,
+}
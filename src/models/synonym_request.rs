@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SynonymGroupRequest {
+    pub index: String,
+    pub group_id: String,
+    /// Terms added to the group; existing terms are kept.
+    pub terms: Vec<String>,
+    /// Only documents indexed from now on see the new terms.
+    pub skip_initial_scan: Option<bool>,
+}
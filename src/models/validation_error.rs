@@ -0,0 +1,20 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error caused by an invalid client request, reported as 400 by the routes.
+#[derive(Debug)]
+pub struct ValidationError(pub String);
+
+impl ValidationError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ValidationError(message.into())
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for ValidationError {}
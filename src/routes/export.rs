@@ -0,0 +1,141 @@
+use crate::models::export_request::ExportRequest;
+use crate::models::search_request::SearchRequest;
+use crate::models::validation_error::ValidationError;
+use crate::utils::export_utils::ExportFormat;
+use crate::AppState;
+use actix_web::web::Bytes;
+use actix_web::{web, HttpResponse};
+use futures_util::stream::{self, StreamExt};
+use log::warn;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+enum ExportState {
+    Page(Value),
+    Cursor(String),
+    Done,
+}
+
+/// Holds the cursor of an export still being streamed. If the stream is dropped before the
+/// last page, because a page failed or the client went away, the cursor is deleted.
+struct OpenCursor {
+    app_state: web::Data<AppState>,
+    token: RefCell<Option<String>>,
+}
+
+impl Drop for OpenCursor {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            let app_state = self.app_state.clone();
+            actix_web::rt::spawn(async move {
+                if let Err(e) = app_state.redis_service.close_cursor(&token).await {
+                    warn!("Could not delete export cursor: {}", e);
+                }
+            });
+        }
+    }
+}
+
+pub async fn export(req: web::Json<ExportRequest>, app_state: web::Data<AppState>) -> HttpResponse {
+    let req = req.into_inner();
+
+    let format = match ExportFormat::parse(req.format.as_deref(), req.columns) {
+        Ok(format) => Rc::new(format),
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("[Export] {}", e)
+            }))
+        }
+    };
+
+    let page_size = req.search.limit.unwrap_or(1000);
+    let file_name = req.search.index.clone().unwrap_or_default().replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '-',
+        "",
+    );
+    let search = SearchRequest {
+        scroll: Some(true),
+        cursor: None,
+        limit: Some(page_size),
+        ..req.search
+    };
+
+    // The first page is read up front so request errors are reported with a status code
+    let first_page = match app_state.redis_service.search(search).await {
+        Ok(page) => page,
+        Err(e) if e.is::<ValidationError>() => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("[Export] {}", e)
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("[Export] {}", e)
+            }))
+        }
+    };
+
+    let header = stream::iter(
+        format
+            .header()
+            .map(|header| Ok::<Bytes, actix_web::Error>(Bytes::from(header))),
+    );
+
+    let open_cursor = Rc::new(OpenCursor {
+        app_state: app_state.clone(),
+        token: RefCell::new(first_page["cursor"].as_str().map(str::to_string)),
+    });
+
+    // Each step renders one page and follows the cursor, so only a page is held in memory
+    let render_format = format.clone();
+    let pages = stream::unfold(ExportState::Page(first_page), move |state| {
+        let app_state = app_state.clone();
+        let format = render_format.clone();
+        let open_cursor = open_cursor.clone();
+        async move {
+            let page = match state {
+                ExportState::Page(page) => page,
+                ExportState::Cursor(cursor) => {
+                    let next = SearchRequest {
+                        cursor: Some(cursor),
+                        limit: Some(page_size),
+                        ..Default::default()
+                    };
+                    match app_state.redis_service.search(next).await {
+                        Ok(page) => page,
+                        Err(e) => {
+                            let error = actix_web::error::ErrorInternalServerError(format!(
+                                "[Export] {}",
+                                e
+                            ));
+                            return Some((Err(error), ExportState::Done));
+                        }
+                    }
+                }
+                ExportState::Done => return None,
+            };
+
+            let chunk = format.render(page["data"].as_array().map(Vec::as_slice).unwrap_or(&[]));
+            let next_state = match page["cursor"].as_str() {
+                Some(cursor) => ExportState::Cursor(cursor.to_string()),
+                None => ExportState::Done,
+            };
+            // A fully read cursor is already gone from Redis
+            *open_cursor.token.borrow_mut() = page["cursor"].as_str().map(str::to_string);
+            Some((Ok(Bytes::from(chunk)), next_state))
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"{}.{}\"",
+                file_name,
+                format.extension()
+            ),
+        ))
+        .streaming(header.chain(pages))
+}
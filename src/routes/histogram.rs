@@ -0,0 +1,19 @@
+use crate::models::histogram_request::HistogramRequest;
+use crate::models::validation_error::ValidationError;
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+
+pub async fn histogram(
+    req: web::Json<HistogramRequest>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    match app_state.redis_service.histogram(req.into_inner()).await {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<ValidationError>() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("[Histogram] {}", e)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("[Histogram] {}", e)
+        })),
+    }
+}
@@ -1,3 +1,7 @@
+use crate::models::conflict_error::ConflictError;
+use crate::models::not_found_error::NotFoundError;
+use crate::models::validation_error::ValidationError;
+use crate::services::redis_service::IndexStart;
 use crate::AppState;
 use actix_web::{web, HttpResponse, Responder};
 
@@ -5,8 +9,47 @@ pub async fn index(
     req_body: web::Json<serde_json::Value>,
     app_state: web::Data<AppState>,
 ) -> impl Responder {
-    match app_state.redis_service.index(req_body.into_inner()).await {
+    match app_state
+        .redis_service
+        .start_index(req_body.into_inner())
+        .await
+    {
+        Ok(IndexStart::Applied(result)) => HttpResponse::Ok().json(result),
+        Ok(IndexStart::Building(build)) => {
+            let status = build.status.clone();
+            // The build outlives the request; it records its outcome in the build status
+            let app_state = app_state.clone();
+            actix_web::rt::spawn(async move {
+                let _ = app_state.redis_service.run_index_build(build).await;
+            });
+            HttpResponse::Accepted().json(serde_json::json!({
+                "status": "accepted",
+                "status_url": format!("/index/{}/build", status.index_name),
+                "build": status
+            }))
+        }
+        Err(e) if e.is::<ValidationError>() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("[Index] {}", e)
+        })),
+        Err(e) if e.is::<ConflictError>() => HttpResponse::Conflict().json(serde_json::json!({
+            "error": format!("[Index] {}", e)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("[Index] {}", e)
+        })),
+    }
+}
+
+pub async fn build_status(
+    path: web::Path<String>,
+    app_state: web::Data<AppState>,
+) -> impl Responder {
+    let name = path.into_inner();
+    match app_state.redis_service.index_build_status(&name).await {
         Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<NotFoundError>() => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("[Index] {}", e)
+        })),
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("[Index] {}", e)
         })),
@@ -0,0 +1,46 @@
+use crate::models::conflict_error::ConflictError;
+use crate::models::drop_index_request::DropIndexQuery;
+use crate::models::not_found_error::NotFoundError;
+use crate::models::validation_error::ValidationError;
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+use serde_json::Value;
+use std::error::Error;
+
+pub async fn indexes(app_state: web::Data<AppState>) -> HttpResponse {
+    respond(app_state.redis_service.indexes().await)
+}
+
+pub async fn drop_index(
+    path: web::Path<String>,
+    query: web::Query<DropIndexQuery>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    let name = path.into_inner();
+    let query = query.into_inner();
+    let delete_documents = query.delete_documents.unwrap_or(false);
+    respond(
+        app_state
+            .redis_service
+            .drop_index(name, query.confirm, delete_documents)
+            .await,
+    )
+}
+
+fn respond(result: Result<Value, Box<dyn Error>>) -> HttpResponse {
+    match result {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<ValidationError>() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("[Indexes] {}", e)
+        })),
+        Err(e) if e.is::<NotFoundError>() => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("[Indexes] {}", e)
+        })),
+        Err(e) if e.is::<ConflictError>() => HttpResponse::Conflict().json(serde_json::json!({
+            "error": format!("[Indexes] {}", e)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("[Indexes] {}", e)
+        })),
+    }
+}
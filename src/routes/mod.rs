@@ -1,9 +1,16 @@
 pub mod add;
+pub mod aggregate;
 pub mod delete;
+pub mod export;
 pub mod hello;
+pub mod histogram;
 pub mod index;
+pub mod indexes;
+pub mod registry;
 pub mod search;
 pub mod status;
+pub mod suggest;
+pub mod synonyms;
 
 use actix_web::web;
 
@@ -12,7 +19,33 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         .service(web::resource("/status/{index}").route(web::get().to(status::status_ft_info)))
         .service(web::resource("/add").route(web::post().to(add::add)))
         .service(web::resource("/search").route(web::post().to(search::search)))
+        .service(web::resource("/aggregate").route(web::post().to(aggregate::aggregate)))
+        .service(web::resource("/histogram").route(web::post().to(histogram::histogram)))
+        .service(web::resource("/export").route(web::post().to(export::export)))
+        .service(web::resource("/suggest").route(web::post().to(suggest::suggest)))
+        .service(web::resource("/suggest/add").route(web::post().to(suggest::add_suggestions)))
+        .service(
+            web::resource("/suggest/delete").route(web::post().to(suggest::delete_suggestions)),
+        )
+        .service(web::resource("/synonyms").route(web::post().to(synonyms::update_synonyms)))
+        .service(web::resource("/synonyms/{index}").route(web::get().to(synonyms::synonyms)))
+        .service(
+            web::resource("/synonyms/{index}/{group_id}")
+                .route(web::delete().to(synonyms::delete_synonym_group)),
+        )
         .service(web::resource("/index").route(web::post().to(index::index)))
+        .service(web::resource("/index/{name}/build").route(web::get().to(index::build_status)))
+        .service(web::resource("/indexes").route(web::get().to(indexes::indexes)))
+        .service(web::resource("/indexes/{name}").route(web::delete().to(indexes::drop_index)))
+        .service(web::resource("/registry").route(web::get().to(registry::registry)))
+        .service(
+            web::resource("/registry/restore").route(web::post().to(registry::restore_registry)),
+        )
+        .service(
+            web::resource("/registry/{name}")
+                .route(web::get().to(registry::registry_entry))
+                .route(web::delete().to(registry::delete_registry_entry)),
+        )
         .service(web::resource("/delete").route(web::post().to(delete::delete)))
         .route("/", web::get().to(hello::greet));
 }
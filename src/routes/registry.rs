@@ -0,0 +1,62 @@
+use crate::models::not_found_error::NotFoundError;
+use crate::models::registry_request::{RegistryDeleteQuery, RestoreRequest};
+use crate::models::validation_error::ValidationError;
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+use serde_json::Value;
+use std::error::Error;
+
+pub async fn registry(app_state: web::Data<AppState>) -> HttpResponse {
+    respond(app_state.redis_service.registry().await)
+}
+
+pub async fn registry_entry(
+    path: web::Path<String>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    let name = path.into_inner();
+    respond(app_state.redis_service.registry_entry(name).await)
+}
+
+pub async fn delete_registry_entry(
+    path: web::Path<String>,
+    query: web::Query<RegistryDeleteQuery>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    let name = path.into_inner();
+    if query.drop_index.unwrap_or(false) {
+        return respond(Err(ValidationError::new(format!(
+            "drop_index is not supported here, use DELETE /indexes/{}?confirm={}",
+            name, name
+        ))
+        .into()));
+    }
+    respond(app_state.redis_service.delete_registry_entry(name).await)
+}
+
+pub async fn restore_registry(
+    req: web::Json<RestoreRequest>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    respond(
+        app_state
+            .redis_service
+            .restore_registry(req.into_inner())
+            .await,
+    )
+}
+
+fn respond(result: Result<Value, Box<dyn Error>>) -> HttpResponse {
+    match result {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<ValidationError>() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("[Registry] {}", e)
+        })),
+        Err(e) if e.is::<NotFoundError>() => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("[Registry] {}", e)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("[Registry] {}", e)
+        })),
+    }
+}
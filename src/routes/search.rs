@@ -1,4 +1,5 @@
 use crate::models::search_request::SearchRequest;
+use crate::models::validation_error::ValidationError;
 use crate::AppState;
 use actix_web::{web, HttpResponse};
 
@@ -12,6 +13,9 @@ pub async fn search(
         .await
     {
         Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<ValidationError>() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("[Search] {}", e)
+        })),
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("[Search] {}", e)
         })),
@@ -1,3 +1,4 @@
+use crate::models::not_found_error::NotFoundError;
 use crate::AppState;
 use actix_web::{web, HttpResponse};
 
@@ -17,6 +18,9 @@ pub async fn status_ft_info(
     let index = path.into_inner(); // Extract the index name from the path
     match app_state.redis_service.ft_status(index).await {
         Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<NotFoundError>() => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("[FT.INFO] {}", e)
+        })),
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("[FT.INFO] {}", e)
         })),
@@ -0,0 +1,51 @@
+use crate::models::suggest_request::{
+    SuggestRequest, SuggestionAddRequest, SuggestionDeleteRequest,
+};
+use crate::models::validation_error::ValidationError;
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+use serde_json::Value;
+use std::error::Error;
+
+pub async fn suggest(
+    req: web::Json<SuggestRequest>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    respond(app_state.redis_service.suggest(req.into_inner()).await)
+}
+
+pub async fn add_suggestions(
+    req: web::Json<SuggestionAddRequest>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    respond(
+        app_state
+            .redis_service
+            .add_suggestions(req.into_inner())
+            .await,
+    )
+}
+
+pub async fn delete_suggestions(
+    req: web::Json<SuggestionDeleteRequest>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    respond(
+        app_state
+            .redis_service
+            .delete_suggestions(req.into_inner())
+            .await,
+    )
+}
+
+fn respond(result: Result<Value, Box<dyn Error>>) -> HttpResponse {
+    match result {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<ValidationError>() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("[Suggest] {}", e)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("[Suggest] {}", e)
+        })),
+    }
+}
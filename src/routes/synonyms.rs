@@ -0,0 +1,48 @@
+use crate::models::synonym_request::SynonymGroupRequest;
+use crate::models::validation_error::ValidationError;
+use crate::AppState;
+use actix_web::{web, HttpResponse};
+use serde_json::Value;
+use std::error::Error;
+
+pub async fn update_synonyms(
+    req: web::Json<SynonymGroupRequest>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    respond(
+        app_state
+            .redis_service
+            .update_synonyms(req.into_inner())
+            .await,
+    )
+}
+
+pub async fn synonyms(path: web::Path<String>, app_state: web::Data<AppState>) -> HttpResponse {
+    let index = path.into_inner();
+    respond(app_state.redis_service.synonyms(index).await)
+}
+
+pub async fn delete_synonym_group(
+    path: web::Path<(String, String)>,
+    app_state: web::Data<AppState>,
+) -> HttpResponse {
+    let (index, group_id) = path.into_inner();
+    respond(
+        app_state
+            .redis_service
+            .delete_synonym_group(index, group_id)
+            .await,
+    )
+}
+
+fn respond(result: Result<Value, Box<dyn Error>>) -> HttpResponse {
+    match result {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) if e.is::<ValidationError>() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("[Synonyms] {}", e)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("[Synonyms] {}", e)
+        })),
+    }
+}
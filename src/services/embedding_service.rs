@@ -0,0 +1,85 @@
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::time::Duration;
+
+/// Client for an OpenAI-compatible embeddings endpoint: it receives
+/// `{"model": ..., "input": [texts]}` and answers `{"data": [{"embedding": [...]}]}`.
+pub struct EmbeddingService {
+    client: reqwest::Client,
+    url: String,
+    model: Option<String>,
+    api_key: Option<String>,
+    batch_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f64>,
+    index: Option<usize>,
+}
+
+impl EmbeddingService {
+    pub fn new(
+        url: String,
+        model: Option<String>,
+        api_key: Option<String>,
+        batch_size: usize,
+        timeout: Duration,
+    ) -> Result<Self, reqwest::Error> {
+        Ok(EmbeddingService {
+            client: reqwest::Client::builder().timeout(timeout).build()?,
+            url,
+            model,
+            api_key,
+            batch_size,
+        })
+    }
+
+    /// Embeds the texts in batches, returning one vector per text in the same order.
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+
+        for batch in texts.chunks(self.batch_size) {
+            debug!("Requesting {} embeddings from {}", batch.len(), self.url);
+
+            let mut request = self.client.post(&self.url).json(&EmbeddingRequest {
+                model: self.model.as_deref(),
+                input: batch,
+            });
+            if let Some(api_key) = &self.api_key {
+                request = request.bearer_auth(api_key);
+            }
+
+            let mut response: EmbeddingResponse =
+                request.send().await?.error_for_status()?.json().await?;
+
+            if response.data.len() != batch.len() {
+                return Err(format!(
+                    "Embedding endpoint returned {} vectors for {} texts",
+                    response.data.len(),
+                    batch.len()
+                )
+                .into());
+            }
+
+            // Results may come back out of order when the endpoint reports their index
+            response.data.sort_by_key(|data| data.index.unwrap_or(0));
+            vectors.extend(response.data.into_iter().map(|data| data.embedding));
+        }
+
+        Ok(vectors)
+    }
+}
@@ -1 +1,2 @@
+pub mod embedding_service;
 pub mod redis_service;
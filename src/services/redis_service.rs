@@ -1,41 +1,160 @@
+use crate::models::aggregate_request::{AggregateRequest, ApplyStep, Reducer, SortKey};
+use crate::models::conflict_error::ConflictError;
 use crate::models::delete_request::DeleteRequest;
-use crate::models::search_request::SearchRequest;
-use crate::utils::date_utils::parse_date_time_with_timezone;
-use log::debug;
+use crate::models::filter::{GeoFilter, RangeFilter};
+use crate::models::histogram_request::HistogramRequest;
+use crate::models::index_build::IndexBuildStatus;
+use crate::models::index_info::IndexInfo;
+use crate::models::index_request::{IndexRegistryEntry, IndexRequest, SchemaField};
+use crate::models::not_found_error::NotFoundError;
+use crate::models::registry_request::RestoreRequest;
+use crate::models::search_request::{ReturnField, SearchRequest, SpellcheckOptions};
+use crate::models::suggest_request::{
+    SuggestRequest, SuggestionAddRequest, SuggestionDeleteRequest,
+};
+use crate::models::synonym_request::SynonymGroupRequest;
+use crate::models::validation_error::ValidationError;
+use crate::services::embedding_service::EmbeddingService;
+use crate::utils::date_utils::{
+    bucket_start, format_with_timezone, next_bucket, parse_date_time_with_timezone, parse_interval,
+    Interval,
+};
+use crate::utils::fusion_utils::fuse_hits;
+use crate::utils::info_utils::parse_index_info;
+use crate::utils::query_utils::{
+    build_query, compile_geo_polygon, compile_geo_radius, compile_knn_query, compile_reducer,
+    compile_return_fields, compile_snippet_args, compile_sort_keys, compile_spellcheck_args,
+    custom_dictionary, decode_cursor, encode_cursor, expression_fields, geo_distance_expression,
+    histogram_expression, rewrite_query, sort_order, validate_field_name, validate_scorer,
+    vector_blob,
+};
+use crate::utils::redis_utils::{field_pairs, value_to_json, value_to_string, value_to_typed_json};
+use crate::utils::schema_utils::{
+    compile_create_args, compile_schema_field, diff_schema, field_names, field_path, get_json_path,
+    is_dotted_path, prefixes_overlap, set_json_path, PlanAction, SchemaPlan,
+};
+use crate::utils::suggest_utils::{
+    parse_suggestions, source_dictionary, suggestion_key, suggestion_refs_key, suggestion_strings,
+};
+use crate::utils::validation_utils::{validate_record, RecordError};
+use log::{debug, warn};
 use mobc::Pool;
 use mobc_redis::{redis, RedisConnectionManager};
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use std::collections::HashMap;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-pub struct RedisService {
-    pool: Arc<Pool<RedisConnectionManager>>,
+const MAX_HISTOGRAM_BUCKETS: usize = 10000;
+
+/// Index registry: a hash of index name to `IndexRegistryEntry` JSON.
+const INDEX_REGISTRY_KEY: &str = "data-node:indexes";
+/// JSON documents holding the synonym groups of each index, suffixed with the index name.
+const SYNONYMS_KEY_PREFIX: &str = "data-node:synonyms:";
+/// How often a registry write is retried when the entry changed since it was read.
+const REGISTRY_WRITE_ATTEMPTS: usize = 5;
+
+/// How long a build waits for a new index version to finish its initial scan.
+const INDEX_BUILD_TIMEOUT: Duration = Duration::from_secs(600);
+const INDEX_BUILD_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Build locks taken by `start_index`, suffixed with the alias. They outlive the longest build.
+const INDEX_LOCK_PREFIX: &str = "data-node:lock:";
+const INDEX_LOCK_TTL: Duration = Duration::from_secs(660);
+/// Status of the last build of each alias, suffixed with the alias. A finished build stays
+/// readable for a day; a running one expires with its lock if the process dies.
+const INDEX_BUILD_STATUS_PREFIX: &str = "data-node:build:";
+const INDEX_BUILD_STATUS_TTL: Duration = Duration::from_secs(86400);
+/// How long the SORTABLE attributes of an index are reused for the default search order.
+const SORTABLE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Applies a batch of suggestion reference changes: KEYS are the refs hash and the
+/// dictionary, ARGV the delta followed by (string, score, payload) triples. Without a
+/// score, the score is the number of documents carrying the string.
+const UPDATE_SUGGESTIONS_SCRIPT: &str = r"
+local delta = tonumber(ARGV[1])
+for i = 2, #ARGV, 3 do
+    local string = ARGV[i]
+    local count = redis.call('HINCRBY', KEYS[1], string, delta)
+    if delta > 0 then
+        local score = ARGV[i + 1]
+        if score == '' then score = count end
+        if ARGV[i + 2] == '' then
+            redis.call('FT.SUGADD', KEYS[2], string, score)
+        else
+            redis.call('FT.SUGADD', KEYS[2], string, score, 'PAYLOAD', ARGV[i + 2])
+        end
+    elseif count <= 0 then
+        redis.call('HDEL', KEYS[1], string)
+        -- A negative count means the string never came from a document
+        if count == 0 then redis.call('FT.SUGDEL', KEYS[2], string) end
+    end
+end
+return 0
+";
+
+/// Replaces a registry entry only if it still holds the value it was read with: KEYS is the
+/// registry, ARGV the index name, the value read (empty when absent) and the new value.
+const REPLACE_REGISTRY_ENTRY_SCRIPT: &str = r"
+local current = redis.call('HGET', KEYS[1], ARGV[1]) or ''
+if current ~= ARGV[2] then return 0 end
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[3])
+return 1
+";
+
+/// Adds terms to one persisted synonym group without touching the others: KEYS is the
+/// synonyms document, ARGV the empty document, the JSONPath of the group, the update time
+/// and the terms, all JSON encoded. Returns the group.
+const UPDATE_SYNONYM_GROUP_SCRIPT: &str = r"
+redis.call('JSON.SET', KEYS[1], '$', ARGV[1], 'NX')
+redis.call('JSON.SET', KEYS[1], ARGV[2], '[]', 'NX')
+for i = 4, #ARGV do
+    if redis.call('JSON.ARRINDEX', KEYS[1], ARGV[2], ARGV[i])[1] == -1 then
+        redis.call('JSON.ARRAPPEND', KEYS[1], ARGV[2], ARGV[i])
+    end
+end
+redis.call('JSON.SET', KEYS[1], '$.updated_at', ARGV[3])
+return redis.call('JSON.GET', KEYS[1], ARGV[2])
+";
+
+/// Query parameters bound with PARAMS: vector blobs and polygon shapes.
+type QueryParams = Vec<(String, Vec<u8>)>;
+
+/// An `/index` call that holds the build lock of its alias and still has to build the next
+/// index version with `run_index_build`.
+pub struct IndexBuild {
+    request: IndexRequest,
+    live_index: Option<String>,
+    plan: SchemaPlan,
+    lock: String,
+    pub status: IndexBuildStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct IndexRequest {
-    index_name: String,
-    #[serde(rename = "type")]
-    index_type: String,
-    language: Option<String>,
-    prefixes: Vec<String>,
-    schema: Vec<SchemaField>,
+/// Outcome of `start_index`.
+pub enum IndexStart {
+    /// Dry runs, in-place alterations and no-ops, already applied.
+    Applied(Value),
+    Building(Box<IndexBuild>),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct SchemaField {
-    field_name: String,
-    field_type: String,
-    sortable: bool,
+pub struct RedisService {
+    pool: Arc<Pool<RedisConnectionManager>>,
+    embedding_service: Option<EmbeddingService>,
+    /// SORTABLE attributes by index name, so plain searches skip FT.INFO.
+    sortable_cache: Mutex<HashMap<String, (Instant, Vec<String>)>>,
 }
 
 impl RedisService {
-    pub fn new(pool: Arc<Pool<RedisConnectionManager>>) -> Self {
-        RedisService { pool }
+    pub fn new(
+        pool: Arc<Pool<RedisConnectionManager>>,
+        embedding_service: Option<EmbeddingService>,
+    ) -> Self {
+        RedisService {
+            pool,
+            embedding_service,
+            sortable_cache: Mutex::new(HashMap::new()),
+        }
     }
 
     pub async fn status(&self) -> Result<Value, Box<dyn std::error::Error>> {
@@ -65,356 +184,2727 @@ impl RedisService {
     }
 
     pub async fn ft_status(&self, index: String) -> Result<Value, Box<dyn Error>> {
-        let mut con = self.pool.get().await?;
-        let response: Vec<redis::Value> = redis::cmd("FT.INFO")
-            .arg(&index)
-            .query_async(&mut *con)
-            .await?;
-
-        // Convert Vec<redis::Value> to Vec<serde_json::Value>, filtering out nulls
-        let json_response = response
-            .into_iter()
-            .filter_map(|val| {
-                // Use filter_map to both map and filter out None values
-                match val {
-                    redis::Value::Nil => None, // Skip nil values
-                    redis::Value::Int(i) => Some(json!(i)),
-                    redis::Value::Data(vec) => {
-                        serde_json::to_value(String::from_utf8(vec).ok()).ok()
-                    }
-                    redis::Value::Bulk(vec) => Some(json!(vec
-                        .into_iter()
-                        .filter_map(|v| match v {
-                            redis::Value::Data(data) =>
-                                serde_json::to_value(String::from_utf8(data).ok()).ok(),
-                            // Skip null values, add cases for other types if necessary
-                            _ => None,
-                        })
-                        .collect::<Vec<Value>>())),
-                    redis::Value::Status(s) => Some(json!(s)),
-                    // Implement other conversions as needed, skipping nulls
-                    _ => None,
-                }
-            })
-            .collect::<Vec<Value>>();
-
-        // Convert Vec<serde_json::Value> to serde_json::Value (JSON Array), already filtered
-        let json_array = Value::Array(json_response);
-
-        Ok(json_array)
+        let info = self.index_info(&index).await?;
+        Ok(serde_json::to_value(info)?)
     }
 
+    /// Applies a definition and waits for the build it starts, if any. Used where the outcome
+    /// is needed right away, such as startup definitions and registry restores.
     pub async fn index(&self, data: Value) -> Result<Value, Box<dyn std::error::Error>> {
+        match self.start_index(data).await? {
+            IndexStart::Applied(response) => Ok(response),
+            IndexStart::Building(build) => self.run_index_build(build).await,
+        }
+    }
+
+    /// Validates a definition and plans it against the live index. Creating or rebuilding an
+    /// index is left to `run_index_build`, which releases the build lock taken here.
+    pub async fn start_index(&self, data: Value) -> Result<IndexStart, Box<dyn Error>> {
         // Deserialize JSON data to IndexRequest struct
         let request: IndexRequest = serde_json::from_value(data)?;
 
         // Check for missing mandatory fields
         if request.index_name.is_empty() {
-            return Err("index_name is missing".into());
+            return Err(ValidationError::new("index_name is missing").into());
         }
 
         if request.index_type.is_empty() {
-            return Err("index_type is missing".into());
+            return Err(ValidationError::new("index_type is missing").into());
         }
 
         if request.prefixes.is_empty() {
-            return Err("prefixes are missing".into());
+            return Err(ValidationError::new("prefixes are missing").into());
         }
 
         if request.schema.is_empty() {
-            return Err("schema is missing".into());
+            return Err(ValidationError::new("schema is missing").into());
         }
 
-        // Start building the Redis command
-        let mut command = format!(
-            "FT.CREATE {} ON {} PREFIX {} ",
-            request.index_name,
-            request.index_type,
-            request.prefixes.len()
-        );
-
-        // Add prefixes
-        for prefix in request.prefixes {
-            command.push_str(&format!("{} ", prefix));
+        for field in request.schema.iter().filter(|field| field.required) {
+            if !is_dotted_path(field_path(field)) {
+                return Err(ValidationError::new(format!(
+                    "'{}' can't be required: /add only checks dotted paths such as $.a.b",
+                    field.field_name
+                ))
+                .into());
+            }
         }
 
-        // Specify language if present
-        if let Some(language) = request.language {
-            command.push_str(&format!("LANGUAGE {} ", language));
+        for source in request.suggest.as_deref().unwrap_or_default() {
+            suggestion_key(&request.index_name, &source_dictionary(source))?;
         }
 
-        // Add schema
-        command.push_str("SCHEMA ");
-        for field in request.schema {
-            command.push_str(&format!("{} {} ", field.field_name, field.field_type));
-            if field.sortable {
-                command.push_str("SORTABLE ");
+        for (name, terms) in request.dictionaries.iter().flatten() {
+            if name.trim().is_empty() || terms.iter().any(|t| t.trim().is_empty()) {
+                return Err(
+                    ValidationError::new("Dictionary names and terms must not be empty").into(),
+                );
             }
         }
 
-        // Trim the trailing space
-        let redis_command = command.trim_end().to_string();
-        debug!("Executing Redis command: {}", redis_command);
+        if request.dry_run {
+            let (live_index, plan) = self.plan_index(&request).await?;
+            return Ok(IndexStart::Applied(
+                json!({"status": "dry_run", "index": live_index, "plan": plan}),
+            ));
+        }
 
-        // Splitting the command string into a vector of words for individual arguments
-        let command_args: Vec<&str> = redis_command.split_whitespace().collect();
+        // One build per alias at a time: concurrent calls would pick the same version
+        let index_name = request.index_name.clone();
+        let lock = self.lock_index(&index_name).await?;
+        let started = self.start_locked_index(request, &lock).await;
+        if !matches!(started, Ok(IndexStart::Building(_))) {
+            self.invalidate_sortable_fields(&index_name);
+            self.unlock_index(&index_name, &lock).await;
+        }
+        started
+    }
 
-        let mut con = self.pool.get().await?;
+    /// Alters the live index in place, or hands the lock over to a build.
+    async fn start_locked_index(
+        &self,
+        request: IndexRequest,
+        lock: &str,
+    ) -> Result<IndexStart, Box<dyn Error>> {
+        let (live_index, plan) = self.plan_index(&request).await?;
+        match (plan.action, live_index) {
+            (PlanAction::Alter | PlanAction::None, Some(live)) => {
+                self.alter_index(&live, &plan.added_fields).await?;
+                let response = json!({
+                    "status": "success",
+                    "message": format!("Index '{}' updated in place.", request.index_name),
+                    "index": live,
+                    "version": index_version(&request.index_name, &live)
+                });
+                Ok(IndexStart::Applied(
+                    self.finish_index(request, response, &plan).await?,
+                ))
+            }
+            (_, live_index) => {
+                let status = IndexBuildStatus {
+                    build_id: Uuid::new_v4().to_string(),
+                    index_name: request.index_name.clone(),
+                    status: "running".to_string(),
+                    plan: json!(plan),
+                    started_at: chrono::Utc::now().to_rfc3339(),
+                    finished_at: None,
+                    result: None,
+                    error: None,
+                };
+                self.save_build_status(&status).await?;
+                Ok(IndexStart::Building(Box::new(IndexBuild {
+                    request,
+                    live_index,
+                    plan,
+                    lock: lock.to_string(),
+                    status,
+                })))
+            }
+        }
+    }
 
-        // Attempt to drop the existing index if it exists
-        let command_drop = format!("FT.DROPINDEX {}", request.index_name);
-        let command_drop_args: Vec<&str> = command_drop.split_whitespace().collect();
-        let drop_response: Result<String, redis::RedisError> = redis::cmd(command_drop_args[0]) // This is "FT.DROPINDEX"
-            .arg(&command_drop_args[1..]) // The rest of the arguments
-            .query_async(&mut *con)
-            .await;
+    /// Builds the next index version of a started `/index` call, releases its build lock and
+    /// records how the build ended.
+    pub async fn run_index_build(&self, build: Box<IndexBuild>) -> Result<Value, Box<dyn Error>> {
+        let IndexBuild {
+            request,
+            live_index,
+            plan,
+            lock,
+            mut status,
+        } = *build;
+        let index_name = request.index_name.clone();
+
+        let result = match self.rebuild_index(&request, live_index).await {
+            Ok(response) => self.finish_index(request, response, &plan).await,
+            Err(e) => Err(e),
+        };
+        self.invalidate_sortable_fields(&index_name);
+        self.unlock_index(&index_name, &lock).await;
 
-        match drop_response {
-            Ok(_) => debug!(
-                "Existing index '{}' was dropped successfully.",
-                request.index_name
-            ),
-            Err(e) => debug!(
-                "No existing index to drop for '{}'. Error: {}",
-                request.index_name, e
-            ),
+        status.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        match &result {
+            Ok(response) => {
+                status.status = "success".to_string();
+                status.result = Some(response.clone());
+            }
+            Err(e) => {
+                warn!("Build of index '{}' failed: {}", index_name, e);
+                status.status = "failed".to_string();
+                status.error = Some(e.to_string());
+            }
         }
+        if let Err(e) = self.save_build_status(&status).await {
+            warn!("Could not record the build of '{}': {}", index_name, e);
+        }
+        result
+    }
 
-        let response: String = redis::cmd(command_args[0]) // This is "FT.CREATE"
-            .arg(&command_args[1..]) // The rest of the arguments
+    /// Returns the status of the last build of an index.
+    pub async fn index_build_status(&self, name: &str) -> Result<Value, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let stored: Option<String> = redis::cmd("GET")
+            .arg(format!("{}{}", INDEX_BUILD_STATUS_PREFIX, name))
             .query_async(&mut *con)
             .await?;
-
-        if response == "OK" {
-            Ok(
-                json!({"status": "success", "message": format!("Index '{}' created successfully.", request.index_name)}),
-            )
-        } else {
-            Err(format!(
-                "Failed to create index '{}': {}",
-                request.index_name, response
-            )
-            .into())
+        match stored {
+            Some(stored) => Ok(serde_json::from_str(&stored)?),
+            None => {
+                Err(NotFoundError::new(format!("No build recorded for index '{}'", name)).into())
+            }
         }
     }
 
-    pub async fn add(&self, data: Vec<Value>) -> Result<Vec<Value>, Box<dyn Error>> {
+    async fn save_build_status(&self, status: &IndexBuildStatus) -> Result<(), Box<dyn Error>> {
+        let ttl = match status.finished_at {
+            Some(_) => INDEX_BUILD_STATUS_TTL,
+            None => INDEX_LOCK_TTL,
+        };
         let mut con = self.pool.get().await?;
+        let _: () = redis::cmd("SET")
+            .arg(format!(
+                "{}{}",
+                INDEX_BUILD_STATUS_PREFIX, status.index_name
+            ))
+            .arg(serde_json::to_string(status)?)
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query_async(&mut *con)
+            .await?;
+        Ok(())
+    }
 
-        let mut responses = Vec::new();
+    /// Finds the live index behind a definition and what `/index` would change on it.
+    async fn plan_index(
+        &self,
+        request: &IndexRequest,
+    ) -> Result<(Option<String>, SchemaPlan), Box<dyn Error>> {
+        let live_index = self.resolve_index(&request.index_name).await?;
+        let plan = match &live_index {
+            Some(live) => {
+                let live_shape = self.index_info(live).await?;
+                let stored = self.index_definition(&request.index_name).await?;
+                diff_schema(request, &live_shape, stored.as_ref())?
+            }
+            None => SchemaPlan::create(),
+        };
+        Ok((live_index, plan))
+    }
 
-        for mut record in data {
-            // Validate the presence and content of the "source" field
-            if let Some(source) = record.get("source").and_then(|s| s.as_str()) {
-                if source.trim().is_empty() {
-                    // Continue to next record if "source" is empty, or return an error based on your error handling policy
-                    continue;
-                }
-            } else {
-                // Continue to next record if "source" is missing, or return an error based on your error handling policy
+    /// Fills the dictionaries of an applied definition and registers it.
+    async fn finish_index(
+        &self,
+        request: IndexRequest,
+        mut response: Value,
+        plan: &SchemaPlan,
+    ) -> Result<Value, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+
+        // FT.DICTADD only adds missing terms
+        for (name, terms) in request.dictionaries.iter().flatten() {
+            if terms.is_empty() {
                 continue;
             }
+            let _: i64 = redis::cmd("FT.DICTADD")
+                .arg(custom_dictionary(&request.index_name, name))
+                .arg(terms)
+                .query_async(&mut *con)
+                .await?;
+        }
 
-            let source = record["source"]
-                .as_str()
-                .unwrap()
-                .to_lowercase()
-                .replace(" ", "_");
-            let key = format!("{}:{}", source, Uuid::new_v4());
-            let created_at = chrono::Utc::now().to_rfc3339();
-            let created_ts = chrono::Utc::now().timestamp();
+        // Keep the definition so ingest can find the fields it has to embed or suggest
+        let index_version = response["version"].as_u64().map(|v| v as u32);
+        drop(con);
+        let entry = self.register_index(request, index_version).await?;
 
-            record["key"] = json!(key);
-            record["created_at"] = json!(created_at);
-            record["created_ts"] = json!(created_ts);
+        response["revision"] = json!(entry.revision);
+        response["plan"] = json!(plan);
+        Ok(response)
+    }
 
-            // Convert the modified JSON back to a string
-            let json_str = serde_json::to_string(&record).unwrap();
+    /// Takes the build lock of an alias, returning the token that releases it. Fails with a
+    /// conflict while another `/index` call holds it; it expires on its own after a crash.
+    async fn lock_index(&self, index_name: &str) -> Result<String, Box<dyn Error>> {
+        let token = Uuid::new_v4().to_string();
+        let mut con = self.pool.get().await?;
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(format!("{}{}", INDEX_LOCK_PREFIX, index_name))
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(INDEX_LOCK_TTL.as_millis() as u64)
+            .query_async(&mut *con)
+            .await?;
 
-            // Store the JSON in Redis using RedisJSON command
-            let _: () = redis::cmd("JSON.SET")
-                .arg(&key)
-                .arg("$")
-                .arg(&json_str)
+        match acquired {
+            Some(_) => Ok(token),
+            None => Err(ConflictError::new(format!(
+                "Index '{}' is already being built, try again later",
+                index_name
+            ))
+            .into()),
+        }
+    }
+
+    /// Releases a build lock, unless it expired and was taken by another call since. A lock
+    /// that can't be released is logged and left to expire.
+    async fn unlock_index(&self, index_name: &str, token: &str) {
+        let released: Result<(), Box<dyn Error>> = async {
+            let mut con = self.pool.get().await?;
+            let _: i64 = redis::Script::new(
+                "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) end return 0",
+            )
+            .key(format!("{}{}", INDEX_LOCK_PREFIX, index_name))
+            .arg(token)
+            .invoke_async(&mut *con)
+            .await?;
+            Ok(())
+        }
+        .await;
+        if let Err(e) = released {
+            warn!(
+                "Could not release the build lock of '{}': {}",
+                index_name, e
+            );
+        }
+    }
+
+    /// Records an accepted definition in the registry, keeping its creation time. The revision
+    /// only moves when the definition changed, so re-applying the same file is a no-op. The
+    /// entry is replaced only if nobody wrote it since it was read, otherwise it is re-read.
+    async fn register_index(
+        &self,
+        definition: IndexRequest,
+        index_version: Option<u32>,
+    ) -> Result<IndexRegistryEntry, Box<dyn Error>> {
+        let name = definition.index_name.clone();
+        for _ in 0..REGISTRY_WRITE_ATTEMPTS {
+            let mut con = self.pool.get().await?;
+            let stored: Option<String> = redis::cmd("HGET")
+                .arg(INDEX_REGISTRY_KEY)
+                .arg(&name)
                 .query_async(&mut *con)
                 .await?;
+            drop(con);
 
-            responses.push(json!({"status": "success", "key": key}));
-        }
+            let previous = match &stored {
+                Some(stored) => Some(self.parse_registry_entry(stored).await?),
+                None => None,
+            };
+            let now = chrono::Utc::now().to_rfc3339();
+            let entry = match previous {
+                Some(previous) => IndexRegistryEntry {
+                    revision: if serde_json::to_value(&previous.definition)?
+                        == serde_json::to_value(&definition)?
+                    {
+                        previous.revision
+                    } else {
+                        previous.revision + 1
+                    },
+                    definition: definition.clone(),
+                    index_version: index_version.or(previous.index_version),
+                    created_at: previous.created_at,
+                    updated_at: now,
+                },
+                None => IndexRegistryEntry {
+                    definition: definition.clone(),
+                    revision: 1,
+                    index_version,
+                    created_at: now.clone(),
+                    updated_at: now,
+                },
+            };
 
-        Ok(responses)
+            if self
+                .replace_registry_entry(&name, stored.as_deref(), &entry)
+                .await?
+            {
+                return Ok(entry);
+            }
+        }
+        Err(ConflictError::new(format!(
+            "The registry entry of '{}' kept changing, try again later",
+            name
+        ))
+        .into())
     }
 
-    pub async fn search(&self, req: SearchRequest) -> Result<Value, Box<dyn std::error::Error>> {
-        let process_start_time = Instant::now();
+    /// Writes a registry entry if it still holds `expected` (`None`: absent). Returns whether
+    /// it was written.
+    async fn replace_registry_entry(
+        &self,
+        name: &str,
+        expected: Option<&str>,
+        entry: &IndexRegistryEntry,
+    ) -> Result<bool, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let replaced: i64 = redis::Script::new(REPLACE_REGISTRY_ENTRY_SCRIPT)
+            .key(INDEX_REGISTRY_KEY)
+            .arg(name)
+            .arg(expected.unwrap_or_default())
+            .arg(serde_json::to_string(entry)?)
+            .invoke_async(&mut *con)
+            .await?;
+        Ok(replaced == 1)
+    }
 
-        let index_name = req.index.ok_or("The 'index' field is required.")?;
+    /// Rewrites the definitions stored bare by earlier versions as registry entries. Run once
+    /// at startup; reads never write.
+    pub async fn migrate_registry(&self) -> Result<usize, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let stored: Vec<(String, String)> = redis::cmd("HGETALL")
+            .arg(INDEX_REGISTRY_KEY)
+            .query_async(&mut *con)
+            .await?;
+        drop(con);
 
-        let query = req.q.unwrap_or_else(|| "*".to_string());
-        let offset = req.offset.unwrap_or(0); // Convert to string for command args
-        let limit = req.limit.unwrap_or(10); // Convert to string for command args
-        let language = req.language.unwrap_or_else(|| "chinese".to_string());
-        let filter_date_field = req
-            .filter_date_by
-            .unwrap_or_else(|| "post_timestamp".to_string());
+        let mut migrated = 0;
+        for (name, stored) in stored {
+            if serde_json::from_str::<IndexRegistryEntry>(&stored).is_ok() {
+                continue;
+            }
+            let entry = match self.parse_registry_entry(&stored).await {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!("Skipping unreadable registry entry '{}': {}", name, e);
+                    continue;
+                }
+            };
+            // An entry written meanwhile is already in the current format
+            if self
+                .replace_registry_entry(&name, Some(&stored), &entry)
+                .await?
+            {
+                migrated += 1;
+            }
+        }
+        Ok(migrated)
+    }
 
-        let query_str = if !query.is_empty() && query != "*" {
-            query.clone()
-        } else {
-            "*".to_string() // Default to "*" if the query is empty
+    /// Reads one registry entry, or all of them sorted by name. Definitions stored bare by
+    /// earlier versions are wrapped in memory until `migrate_registry` rewrites them;
+    /// unreadable entries are logged and skipped.
+    async fn registry_entries(
+        &self,
+        name: Option<&str>,
+    ) -> Result<Vec<IndexRegistryEntry>, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let stored: Vec<(String, String)> = match name {
+            Some(name) => {
+                let entry: Option<String> = redis::cmd("HGET")
+                    .arg(INDEX_REGISTRY_KEY)
+                    .arg(name)
+                    .query_async(&mut *con)
+                    .await?;
+                entry
+                    .map(|entry| (name.to_string(), entry))
+                    .into_iter()
+                    .collect()
+            }
+            None => {
+                redis::cmd("HGETALL")
+                    .arg(INDEX_REGISTRY_KEY)
+                    .query_async(&mut *con)
+                    .await?
+            }
         };
+        drop(con);
 
-        // Sort parameters
-        let sortby_field = req.sort_by.unwrap_or_else(|| "post_timestamp".to_string());
-        let sort_order = req.sort_order.unwrap_or_else(|| "DESC".to_string());
-        let offset_str = offset.to_string();
-        let limit_str = limit.to_string();
+        let mut entries = Vec::new();
+        for (field, entry) in stored {
+            match self.parse_registry_entry(&entry).await {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping unreadable registry entry '{}': {}", field, e),
+            }
+        }
+        entries.sort_by(|a, b| a.definition.index_name.cmp(&b.definition.index_name));
+        Ok(entries)
+    }
 
-        let filter_str =
-            if let (Some(start_time_str), Some(end_time_str)) = (&req.start_time, &req.end_time) {
-                let start_time = parse_date_time_with_timezone(start_time_str, 8);
-                let end_time = parse_date_time_with_timezone(end_time_str, 8);
-
-                vec![
-                    "FILTER".to_string(),
-                    filter_date_field,
-                    start_time.timestamp().to_string(),
-                    end_time.timestamp().to_string(),
-                ]
-            } else {
-                Vec::new() // No filter if no start and end times
-            };
+    /// Parses a stored registry entry. A bare definition stored by an earlier version is
+    /// wrapped as revision 1, recovering the version of the index behind its alias.
+    async fn parse_registry_entry(
+        &self,
+        stored: &str,
+    ) -> Result<IndexRegistryEntry, Box<dyn Error>> {
+        if let Ok(entry) = serde_json::from_str::<IndexRegistryEntry>(stored) {
+            return Ok(entry);
+        }
+        let definition = serde_json::from_str::<IndexRequest>(stored)?;
+        let index_version = self
+            .resolve_index(&definition.index_name)
+            .await?
+            .and_then(|physical| index_version(&definition.index_name, &physical));
+        let now = chrono::Utc::now().to_rfc3339();
+        Ok(IndexRegistryEntry {
+            definition,
+            revision: 1,
+            index_version,
+            created_at: now.clone(),
+            updated_at: now,
+        })
+    }
 
-        // Prepare command arguments with conditional inclusion of filter
-        let mut command_args = vec![
-            index_name.as_str(),
-            query_str.as_str(),
-            "LIMIT",
-            &offset_str,
-            &limit_str,
-            "LANGUAGE",
-            &language,
-            "SORTBY",
-            &sortby_field,
-            &sort_order,
-        ];
+    pub async fn registry(&self) -> Result<Value, Box<dyn Error>> {
+        let entries = self.registry_entries(None).await?;
+        Ok(json!({"data": entries, "total": entries.len()}))
+    }
 
-        if !filter_str.is_empty() {
-            // Convert Vec<String> to Vec<&str> to match types
-            let filter_str_refs: Vec<&str> = filter_str.iter().map(AsRef::as_ref).collect();
-            command_args.extend_from_slice(&filter_str_refs);
+    pub async fn registry_entry(&self, name: String) -> Result<Value, Box<dyn Error>> {
+        match self.registry_entries(Some(&name)).await?.pop() {
+            Some(entry) => Ok(json!(entry)),
+            None => Err(NotFoundError::new(format!("Index '{}' is not registered", name)).into()),
         }
+    }
 
-        // Log the command for debugging
-        debug!(
-            "Executing Redis command: FT.SEARCH {}",
-            command_args.join(" ")
-        );
-
-        // Obtain a connection from the pool
+    /// Removes a definition from the registry and leaves the index in place (see `drop_index`).
+    pub async fn delete_registry_entry(&self, name: String) -> Result<Value, Box<dyn Error>> {
         let mut con = self.pool.get().await?;
-
-        let raw_search_results: Vec<redis::Value> = redis::cmd("FT.SEARCH")
-            .arg(&command_args[..]) // Pass the arguments as a slice
+        let removed: i64 = redis::cmd("HDEL")
+            .arg(INDEX_REGISTRY_KEY)
+            .arg(&name)
             .query_async(&mut *con)
             .await?;
+        if removed == 0 {
+            return Err(NotFoundError::new(format!("Index '{}' is not registered", name)).into());
+        }
 
-        // Extract total_hits from the first element of the response
-        let total_hits = match raw_search_results.get(0) {
-            Some(redis::Value::Int(total)) => *total as u32,
-            _ => 0,
-        };
+        Ok(json!({"status": "success", "index": name}))
+    }
 
-        // Only process the rest of the response if there are hits
-        let documents = if total_hits > 0 {
-            raw_search_results
-                .into_iter()
-                .enumerate()
-                .filter_map(|(index, value)| {
-                    if index >= 2 && index % 2 == 0 {
-                        match value {
-                            redis::Value::Bulk(items) => items.get(3).and_then(|data| match data {
-                                redis::Value::Data(bytes) => {
-                                    serde_json::from_slice::<Value>(bytes).ok()
-                                }
-                                _ => None,
-                            }),
-                            _ => None,
-                        }
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<Value>>()
+    /// Lists the indexes from FT._LIST with the alias they serve and their main FT.INFO counters.
+    pub async fn indexes(&self) -> Result<Value, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let mut names: Vec<String> = redis::cmd("FT._LIST").query_async(&mut *con).await?;
+        drop(con);
+        names.sort();
+
+        let aliases = self.registered_aliases().await?;
+        let mut indexes = Vec::new();
+        for name in names {
+            let info = match self.index_info(&name).await {
+                Ok(info) => info,
+                // Dropped between FT._LIST and FT.INFO.
+                Err(e) if e.is::<NotFoundError>() => continue,
+                Err(e) => return Err(e),
+            };
+            indexes.push(json!({
+                "index": name,
+                "alias": aliases.get(&name),
+                "type": info.index_definition.key_type,
+                "prefixes": info.index_definition.prefixes,
+                "fields": info.attributes.len(),
+                "num_docs": info.num_docs,
+                "num_terms": info.num_terms,
+                "num_records": info.num_records,
+                "indexing": info.indexing,
+                "percent_indexed": info.percent_indexed,
+                "hash_indexing_failures": info.hash_indexing_failures,
+                "total_index_memory_sz_mb": info.memory.total_index_memory_sz_mb,
+            }));
+        }
+
+        Ok(json!({"status": "success", "total": indexes.len(), "data": indexes}))
+    }
+
+    /// Drops an index, given by alias or physical name, and forgets its registry entry.
+    /// `confirm` must repeat the name. With `delete_documents`, the indexed documents are
+    /// deleted too (FT.DROPINDEX DD) along with the suggestion dictionaries fed from them.
+    pub async fn drop_index(
+        &self,
+        name: String,
+        confirm: Option<String>,
+        delete_documents: bool,
+    ) -> Result<Value, Box<dyn Error>> {
+        if confirm.as_deref() != Some(name.as_str()) {
+            return Err(ValidationError::new(format!(
+                "Dropping '{}' must be confirmed with confirm={}",
+                name, name
+            ))
+            .into());
+        }
+
+        let physical = self
+            .resolve_index(&name)
+            .await?
+            .ok_or_else(|| NotFoundError::new(format!("Index '{}' does not exist", name)))?;
+        let alias = if physical == name {
+            self.registered_aliases().await?.remove(&physical)
         } else {
-            Vec::new()
+            Some(name.clone())
         };
 
-        let processing_time_ms = process_start_time.elapsed().as_millis();
-        let page = offset / limit + 1;
-        let total_pages = (total_hits + limit as u32 - 1) / limit as u32;
+        if delete_documents {
+            self.check_exclusive_prefixes(&physical).await?;
+        }
 
-        let response = json!({
-            "data": documents,
-            "query": &query,
-            "totals": total_hits,
-            "processing_time_ms": processing_time_ms,
-            "limit": limit,
-            "offset": offset,
-            "page": page,
-            "totalPages": total_pages
-        });
+        self.drop_physical_index(
+            alias.as_deref().unwrap_or(&name),
+            &physical,
+            delete_documents,
+        )
+        .await?;
+        for dropped in [Some(&name), Some(&physical), alias.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            self.invalidate_sortable_fields(dropped);
+        }
 
-        Ok(response)
+        let mut unregistered = None;
+        if let Some(alias) = &alias {
+            let definition = self.index_definition(alias).await?;
+            let mut con = self.pool.get().await?;
+            let _: i64 = redis::cmd("HDEL")
+                .arg(INDEX_REGISTRY_KEY)
+                .arg(alias)
+                .query_async(&mut *con)
+                .await?;
+
+            if delete_documents {
+                for source in definition
+                    .as_ref()
+                    .and_then(|d| d.suggest.as_deref())
+                    .unwrap_or_default()
+                {
+                    let dictionary_key = suggestion_key(alias, &source_dictionary(source))?;
+                    let _: i64 = redis::cmd("UNLINK")
+                        .arg(suggestion_refs_key(&dictionary_key))
+                        .arg(&dictionary_key)
+                        .query_async(&mut *con)
+                        .await?;
+                }
+            }
+            unregistered = definition.map(|_| alias.clone());
+        }
+
+        Ok(json!({
+            "status": "success",
+            "index": physical,
+            "alias": alias,
+            "documents_deleted": delete_documents,
+            "unregistered": unregistered
+        }))
     }
 
-    pub async fn delete(&self, req: DeleteRequest) -> Result<Value, Box<dyn Error>> {
-        if req.source.is_none()
-            && (req.keys.is_none() || req.keys.as_ref().map(|k| k.is_empty()).unwrap_or(true))
-        {
-            return Err("Either source or keys must be provided and not empty.".into());
+    /// Fails with a conflict when another index covers some of the keys of `physical`, e.g.
+    /// an older version of the same alias, since FT.DROPINDEX DD would delete its documents.
+    async fn check_exclusive_prefixes(&self, physical: &str) -> Result<(), Box<dyn Error>> {
+        let prefixes = self.index_info(physical).await?.index_definition.prefixes;
+
+        let mut con = self.pool.get().await?;
+        let names: Vec<String> = redis::cmd("FT._LIST").query_async(&mut *con).await?;
+        drop(con);
+
+        for other in names.iter().filter(|other| *other != physical) {
+            let info = match self.index_info(other).await {
+                Ok(info) => info,
+                Err(e) if e.is::<NotFoundError>() => continue,
+                Err(e) => return Err(e),
+            };
+            if prefixes_overlap(&prefixes, &info.index_definition.prefixes) {
+                return Err(ConflictError::new(format!(
+                    "Index '{}' shares key prefixes with '{}', drop it without delete_documents",
+                    physical, other
+                ))
+                .into());
+            }
         }
+        Ok(())
+    }
 
+    /// Removes the alias, if the index is behind one, then runs FT.DROPINDEX.
+    async fn drop_physical_index(
+        &self,
+        name: &str,
+        physical: &str,
+        delete_documents: bool,
+    ) -> Result<(), Box<dyn Error>> {
         let mut con = self.pool.get().await?;
+        if physical != name {
+            let response: Result<String, redis::RedisError> = redis::cmd("FT.ALIASDEL")
+                .arg(name)
+                .query_async(&mut *con)
+                .await;
+            // FT.ALIASDEL fails for an alias that no longer exists.
+            if let Err(e) = response {
+                warn!("Could not remove alias '{}': {}", name, e);
+            }
+        }
 
-        // If a prefix is provided, delete keys matching the prefix.
-        if let Some(source) = &req.source {
-            let mut cursor = 0;
-            loop {
-                let (next_cursor, keys): (i64, Vec<String>) = redis::cmd("SCAN")
-                    .arg(cursor)
-                    .arg("MATCH")
-                    .arg(format!("{}:*", source))
-                    .arg("COUNT")
-                    .arg(1000) // Adjust count based on your use case
-                    .query_async(&mut *con)
-                    .await?;
+        let mut command = redis::cmd("FT.DROPINDEX");
+        command.arg(physical);
+        if delete_documents {
+            command.arg("DD");
+        }
+        let _: String = command.query_async(&mut *con).await?;
+        Ok(())
+    }
 
-                if !keys.is_empty() {
-                    // Using UNLINK instead of DEL for potentially non-blocking deletion in newer Redis versions
-                    let _: () = redis::cmd("UNLINK")
-                        .arg(keys)
-                        .query_async(&mut *con)
-                        .await?;
-                }
+    /// Maps the physical index of each registered definition to its alias.
+    async fn registered_aliases(&self) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        Ok(self
+            .registry_entries(None)
+            .await?
+            .into_iter()
+            .map(|entry| {
+                let alias = entry.definition.index_name;
+                let physical = match entry.index_version {
+                    Some(version) => format!("{}_v{}", alias, version),
+                    None => alias.clone(),
+                };
+                (physical, alias)
+            })
+            .collect())
+    }
 
-                cursor = next_cursor;
-                if cursor == 0 {
-                    break;
+    /// Recreates indexes from definitions. Without explicit definitions, every registered
+    /// index that does not exist in Redis is created; one failure does not stop the others.
+    pub async fn restore_registry(&self, req: RestoreRequest) -> Result<Value, Box<dyn Error>> {
+        let definitions = match req.definitions {
+            Some(definitions) => definitions,
+            None => {
+                let mut missing = Vec::new();
+                for entry in self.registry_entries(None).await? {
+                    if self
+                        .resolve_index(&entry.definition.index_name)
+                        .await?
+                        .is_none()
+                    {
+                        missing.push(entry.definition);
+                    }
                 }
+                missing
             }
+        };
+
+        let mut results = Vec::new();
+        for definition in definitions {
+            let name = definition.index_name.clone();
+            let result = match self.index(serde_json::to_value(definition)?).await {
+                Ok(response) => json!({
+                    "index": name,
+                    "status": "success",
+                    "action": response["plan"]["action"],
+                    "version": response["version"]
+                }),
+                Err(e) => json!({"index": name, "status": "failed", "error": e.to_string()}),
+            };
+            results.push(result);
         }
 
-        // If specific keys are provided, delete them directly.
-        if let Some(keys) = &req.keys {
-            if !keys.is_empty() {
-                let _: () = redis::cmd("UNLINK")
-                    .arg(keys)
-                    .query_async(&mut *con)
-                    .await?;
-            }
+        Ok(json!({"status": "success", "data": results}))
+    }
+
+    /// Adds new fields to a live index with FT.ALTER; documents are rescanned in the background.
+    async fn alter_index(
+        &self,
+        index_name: &str,
+        fields: &[SchemaField],
+    ) -> Result<(), Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        for field in fields {
+            let field_args = compile_schema_field(field)?;
+            debug!(
+                "Executing Redis command: FT.ALTER {} SCHEMA ADD {}",
+                index_name,
+                field_args.join(" ")
+            );
+            let _: String = redis::cmd("FT.ALTER")
+                .arg(index_name)
+                .arg("SCHEMA")
+                .arg("ADD")
+                .arg(&field_args)
+                .query_async(&mut *con)
+                .await?;
         }
+        Ok(())
+    }
 
-        Ok(json!({"status": "success"}))
+    /// Builds the next version of an index and moves the alias to it once it is fully indexed.
+    async fn rebuild_index(
+        &self,
+        request: &IndexRequest,
+        live_index: Option<String>,
+    ) -> Result<Value, Box<dyn Error>> {
+        // The alias keeps serving the live version while the next one is built
+        let version = live_index
+            .as_deref()
+            .and_then(|live| index_version(&request.index_name, live))
+            .map_or(1, |version| version + 1);
+        let physical_name = format!("{}_v{}", request.index_name, version);
+
+        let mut physical_request = request.clone();
+        physical_request.index_name = physical_name.clone();
+        let command_args = compile_create_args(&physical_request)?;
+        let groups = self.synonym_groups(&request.index_name).await?;
+
+        let mut con = self.pool.get().await?;
+
+        // A build interrupted before the alias moved can leave this version behind
+        let leftover: Result<String, redis::RedisError> = redis::cmd("FT.DROPINDEX")
+            .arg(&physical_name)
+            .query_async(&mut *con)
+            .await;
+        if leftover.is_ok() {
+            debug!("Dropped leftover index '{}'.", physical_name);
+        }
+
+        debug!(
+            "Executing Redis command: FT.CREATE {}",
+            command_args.join(" ")
+        );
+        let response: String = redis::cmd("FT.CREATE")
+            .arg(&command_args)
+            .query_async(&mut *con)
+            .await?;
+        if response != "OK" {
+            return Err(format!("Failed to create index '{}': {}", physical_name, response).into());
+        }
+
+        // A new version starts without synonyms, the persisted groups are put back
+        for (group_id, terms) in &groups {
+            let _: String = redis::cmd("FT.SYNUPDATE")
+                .arg(&physical_name)
+                .arg(group_id)
+                .arg(terms)
+                .query_async(&mut *con)
+                .await?;
+        }
+
+        // The connection goes back to the pool for the duration of the scan
+        drop(con);
+        let indexed = self.wait_for_indexing(&physical_name).await;
+        let mut con = self.pool.get().await?;
+        if let Err(e) = indexed {
+            let _: Result<String, redis::RedisError> = redis::cmd("FT.DROPINDEX")
+                .arg(&physical_name)
+                .query_async(&mut *con)
+                .await;
+            return Err(e);
+        }
+
+        // An index created before aliases were used holds the alias name itself and has to
+        // go first; both run in one transaction so no search finds the name missing
+        if live_index.as_deref() == Some(request.index_name.as_str()) {
+            let _: () = redis::pipe()
+                .atomic()
+                .cmd("FT.DROPINDEX")
+                .arg(&request.index_name)
+                .ignore()
+                .cmd("FT.ALIASUPDATE")
+                .arg(&request.index_name)
+                .arg(&physical_name)
+                .ignore()
+                .query_async(&mut *con)
+                .await?;
+        } else {
+            let _: String = redis::cmd("FT.ALIASUPDATE")
+                .arg(&request.index_name)
+                .arg(&physical_name)
+                .query_async(&mut *con)
+                .await?;
+        }
+
+        if let Some(live) = live_index.as_deref().filter(|l| *l != request.index_name) {
+            let dropped: Result<String, redis::RedisError> = redis::cmd("FT.DROPINDEX")
+                .arg(live)
+                .query_async(&mut *con)
+                .await;
+            if let Err(e) = dropped {
+                warn!("Failed to drop previous index '{}': {}", live, e);
+            }
+        }
+
+        Ok(json!({
+            "status": "success",
+            "message": format!("Index '{}' created successfully.", request.index_name),
+            "index": physical_name,
+            "version": version,
+            "previous": live_index
+        }))
+    }
+
+    /// Loads the registered definition of one index.
+    async fn index_definition(&self, name: &str) -> Result<Option<IndexRequest>, Box<dyn Error>> {
+        Ok(self
+            .registry_entries(Some(name))
+            .await?
+            .pop()
+            .map(|entry| entry.definition))
+    }
+
+    /// Runs FT.INFO and parses the reply; unknown indexes are reported as not found.
+    async fn index_info(&self, name: &str) -> Result<IndexInfo, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let response: Result<Vec<redis::Value>, redis::RedisError> =
+            redis::cmd("FT.INFO").arg(name).query_async(&mut *con).await;
+
+        match response {
+            Ok(info) => Ok(parse_index_info(&info)),
+            Err(e) if is_unknown_index(&e) => {
+                Err(NotFoundError::new(format!("Index '{}' does not exist", name)).into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns the physical index behind an alias (or a plain index of that name), if any.
+    async fn resolve_index(&self, name: &str) -> Result<Option<String>, Box<dyn Error>> {
+        match self.index_info(name).await {
+            Ok(info) => Ok(Some(info.index_name)),
+            Err(e) if e.is::<NotFoundError>() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Polls FT.INFO until the background scan of a new index is over.
+    async fn wait_for_indexing(&self, index_name: &str) -> Result<(), Box<dyn Error>> {
+        let started = Instant::now();
+        loop {
+            let info = self.index_info(index_name).await?;
+            if !info.indexing {
+                return Ok(());
+            }
+            if started.elapsed() > INDEX_BUILD_TIMEOUT {
+                return Err(format!(
+                    "Index '{}' is still indexing after {}s ({:.0}% done)",
+                    index_name,
+                    INDEX_BUILD_TIMEOUT.as_secs(),
+                    info.percent_indexed * 100.0
+                )
+                .into());
+            }
+            tokio::time::sleep(INDEX_BUILD_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Stores the records as JSON documents under `<source>:<uuid>`. Each record gets its own
+    /// entry in the response, in request order: records without a `source`, or that do not
+    /// match the schema of an index covering their key, are reported and not stored.
+    pub async fn add(&self, data: Vec<Value>) -> Result<Vec<Value>, Box<dyn Error>> {
+        let mut responses: Vec<Option<Value>> = vec![None; data.len()];
+        let mut positions = Vec::new();
+        let mut records = Vec::new();
+
+        for (position, mut record) in data.into_iter().enumerate() {
+            // Validate the presence and content of the "source" field
+            let source = match record.get("source").and_then(|s| s.as_str()) {
+                Some(source) if !source.trim().is_empty() => {
+                    source.to_lowercase().replace(" ", "_")
+                }
+                _ => {
+                    responses[position] = Some(json!({
+                        "status": "error",
+                        "errors": [{"field": "source", "error": "is required"}]
+                    }));
+                    continue;
+                }
+            };
+
+            let key = format!("{}:{}", source, Uuid::new_v4());
+            let created_at = chrono::Utc::now().to_rfc3339();
+            let created_ts = chrono::Utc::now().timestamp();
+
+            record["key"] = json!(key);
+            record["created_at"] = json!(created_at);
+            record["created_ts"] = json!(created_ts);
+
+            positions.push(position);
+            records.push((key, record));
+        }
+
+        // Vector fields sourced from text are filled in before the documents are checked
+        let definitions = self.index_definitions().await?;
+        let embedding_errors = self.embed_records(&definitions, &mut records).await;
+
+        let mut valid_records = Vec::new();
+        for ((position, (key, record)), embedding_errors) in
+            positions.into_iter().zip(records).zip(embedding_errors)
+        {
+            let errors = if embedding_errors.is_empty() {
+                validate_record(&definitions, &key, &record)
+            } else {
+                embedding_errors
+            };
+            if errors.is_empty() {
+                valid_records.push((position, key, record));
+            } else {
+                responses[position] = Some(json!({"status": "error", "errors": errors}));
+            }
+        }
+
+        let mut con = self.pool.get().await?;
+        let mut records = Vec::new();
+
+        for (position, key, record) in valid_records {
+            // Convert the modified JSON back to a string
+            let json_str = serde_json::to_string(&record).unwrap();
+
+            // Store the JSON in Redis using RedisJSON command
+            let _: () = redis::cmd("JSON.SET")
+                .arg(&key)
+                .arg("$")
+                .arg(&json_str)
+                .query_async(&mut *con)
+                .await?;
+
+            responses[position] = Some(json!({"status": "success", "key": key}));
+            records.push((key, record));
+        }
+
+        self.update_suggestions(&mut con, &definitions, &records, 1)
+            .await?;
+
+        Ok(responses.into_iter().flatten().collect())
+    }
+
+    /// Adds (`delta` 1) or removes (`delta` -1) the records' strings in the suggestion
+    /// dictionaries configured on their indexes. Strings are reference counted, so a
+    /// suggestion stays until the last document carrying it is deleted. Each dictionary is
+    /// updated by one script call, so counters and dictionary can't drift apart.
+    async fn update_suggestions(
+        &self,
+        con: &mut redis::aio::Connection,
+        definitions: &[IndexRequest],
+        records: &[(String, Value)],
+        delta: i64,
+    ) -> Result<(), Box<dyn Error>> {
+        let script = redis::Script::new(UPDATE_SUGGESTIONS_SCRIPT);
+
+        for definition in definitions {
+            for source in definition.suggest.as_deref().unwrap_or_default() {
+                let dictionary_key =
+                    suggestion_key(&definition.index_name, &source_dictionary(source))?;
+                let refs_key = suggestion_refs_key(&dictionary_key);
+
+                // (string, score, payload) triples; empty score or payload means none
+                let mut entries: Vec<String> = Vec::new();
+                for (key, record) in records {
+                    if !definition
+                        .prefixes
+                        .iter()
+                        .any(|p| key.starts_with(p.as_str()))
+                    {
+                        continue;
+                    }
+                    let score = source
+                        .score_field
+                        .as_deref()
+                        .and_then(|f| get_json_path(record, f))
+                        .and_then(Value::as_f64)
+                        .map(|score| score.to_string())
+                        .unwrap_or_default();
+                    let payload = source
+                        .payload_field
+                        .as_deref()
+                        .and_then(|f| get_json_path(record, f))
+                        .map(|v| match v {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        })
+                        .unwrap_or_default();
+
+                    for string in suggestion_strings(record, &source.field) {
+                        entries.extend([string, score.clone(), payload.clone()]);
+                    }
+                }
+                if entries.is_empty() {
+                    continue;
+                }
+
+                let _: i64 = script
+                    .key(&refs_key)
+                    .key(&dictionary_key)
+                    .arg(delta)
+                    .arg(&entries)
+                    .invoke_async(con)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds terms to a synonym group and persists the group so `index` can re-apply it.
+    pub async fn update_synonyms(&self, req: SynonymGroupRequest) -> Result<Value, Box<dyn Error>> {
+        if req.index.trim().is_empty() {
+            return Err(ValidationError::new("The 'index' field is required.").into());
+        }
+        if req.group_id.trim().is_empty() {
+            return Err(ValidationError::new("The 'group_id' field is required.").into());
+        }
+        let terms = req
+            .terms
+            .iter()
+            .map(|t| t.trim().to_string())
+            .collect::<Vec<String>>();
+        if terms.is_empty() || terms.iter().any(String::is_empty) {
+            return Err(ValidationError::new("Synonym terms must not be empty").into());
+        }
+
+        let mut command = redis::cmd("FT.SYNUPDATE");
+        command.arg(&req.index).arg(&req.group_id);
+        if req.skip_initial_scan.unwrap_or(false) {
+            command.arg("SKIPINITIALSCAN");
+        }
+        command.arg(&terms);
+
+        let mut con = self.pool.get().await?;
+        let _: String = command.query_async(&mut *con).await?;
+        drop(con);
+
+        // FT.SYNUPDATE only ever adds terms, so the stored group is the union
+        let empty_document = json!({"index": &req.index, "groups": {}});
+        let mut con = self.pool.get().await?;
+        let stored: String = redis::Script::new(UPDATE_SYNONYM_GROUP_SCRIPT)
+            .key(synonyms_key(&req.index))
+            .arg(empty_document.to_string())
+            .arg(synonym_group_path(&req.group_id))
+            .arg(json!(chrono::Utc::now().to_rfc3339()).to_string())
+            .arg(
+                terms
+                    .iter()
+                    .map(|term| json!(term).to_string())
+                    .collect::<Vec<String>>(),
+            )
+            .invoke_async(&mut *con)
+            .await?;
+
+        // The group path matches once, its terms come back wrapped in an array
+        let group = serde_json::from_str::<Vec<Value>>(&stored)?
+            .pop()
+            .unwrap_or_else(|| json!(terms));
+        Ok(json!({"status": "success", "group_id": req.group_id, "terms": group}))
+    }
+
+    /// Returns the persisted groups next to the term-to-groups map from FT.SYNDUMP.
+    pub async fn synonyms(&self, index: String) -> Result<Value, Box<dyn Error>> {
+        let groups = self.synonym_groups(&index).await?;
+
+        let mut con = self.pool.get().await?;
+        let dump: Vec<redis::Value> = redis::cmd("FT.SYNDUMP")
+            .arg(&index)
+            .query_async(&mut *con)
+            .await?;
+        let terms = field_pairs(&dump)
+            .into_iter()
+            .map(|(term, groups)| (term, value_to_json(groups)))
+            .collect::<Map<String, Value>>();
+
+        Ok(json!({"index": index, "groups": groups, "terms": terms}))
+    }
+
+    /// Forgets a synonym group. RediSearch can't remove synonyms from a live index, so the
+    /// group only disappears once the index is recreated with `index`.
+    pub async fn delete_synonym_group(
+        &self,
+        index: String,
+        group_id: String,
+    ) -> Result<Value, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let deleted: i64 = redis::cmd("JSON.DEL")
+            .arg(synonyms_key(&index))
+            .arg(synonym_group_path(&group_id))
+            .query_async(&mut *con)
+            .await?;
+        if deleted == 0 {
+            return Err(ValidationError::new(format!(
+                "Synonym group '{}' does not exist on index '{}'",
+                group_id, index
+            ))
+            .into());
+        }
+        let _: () = redis::cmd("JSON.SET")
+            .arg(synonyms_key(&index))
+            .arg("$.updated_at")
+            .arg(json!(chrono::Utc::now().to_rfc3339()).to_string())
+            .query_async(&mut *con)
+            .await?;
+
+        Ok(json!({
+            "status": "success",
+            "message": format!("Synonym group '{}' removed, it stays active until index '{}' is recreated.", group_id, index)
+        }))
+    }
+
+    async fn synonym_groups(
+        &self,
+        index: &str,
+    ) -> Result<BTreeMap<String, Vec<String>>, Box<dyn Error>> {
+        let mut con = self.pool.get().await?;
+        let document: Option<String> = redis::cmd("JSON.GET")
+            .arg(synonyms_key(index))
+            .arg("$.groups")
+            .query_async(&mut *con)
+            .await?;
+
+        // `$.groups` wraps the value in an array
+        let groups = document
+            .and_then(|document| serde_json::from_str::<Vec<Value>>(&document).ok())
+            .and_then(|mut matches| matches.pop())
+            .and_then(|groups| serde_json::from_value(groups).ok())
+            .unwrap_or_default();
+        Ok(groups)
+    }
+
+    pub async fn suggest(&self, req: SuggestRequest) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        let dictionary_key = suggestion_key(&req.index, &req.dictionary)?;
+        if req.prefix.trim().is_empty() {
+            return Err(ValidationError::new("The 'prefix' field is required.").into());
+        }
+        let with_scores = req.with_scores.unwrap_or(false);
+        let with_payloads = req.with_payloads.unwrap_or(false);
+
+        let mut command = redis::cmd("FT.SUGGET");
+        command
+            .arg(&dictionary_key)
+            .arg(req.prefix.trim())
+            .arg("MAX")
+            .arg(req.max.unwrap_or(5));
+        if req.fuzzy.unwrap_or(false) {
+            command.arg("FUZZY");
+        }
+        if with_scores {
+            command.arg("WITHSCORES");
+        }
+        if with_payloads {
+            command.arg("WITHPAYLOADS");
+        }
+
+        let mut con = self.pool.get().await?;
+        let reply: Vec<redis::Value> = command.query_async(&mut *con).await?;
+
+        Ok(json!({
+            "data": parse_suggestions(&reply, with_scores, with_payloads),
+            "prefix": req.prefix,
+            "processing_time_ms": process_start_time.elapsed().as_millis()
+        }))
+    }
+
+    pub async fn add_suggestions(
+        &self,
+        req: SuggestionAddRequest,
+    ) -> Result<Value, Box<dyn Error>> {
+        let dictionary_key = suggestion_key(&req.index, &req.dictionary)?;
+        if req.suggestions.iter().any(|s| s.string.trim().is_empty()) {
+            return Err(ValidationError::new("Suggestion strings must not be empty").into());
+        }
+
+        let mut con = self.pool.get().await?;
+        for suggestion in &req.suggestions {
+            let mut command = redis::cmd("FT.SUGADD");
+            command
+                .arg(&dictionary_key)
+                .arg(suggestion.string.trim())
+                .arg(suggestion.score.unwrap_or(1.0));
+            if suggestion.incr.unwrap_or(false) {
+                command.arg("INCR");
+            }
+            if let Some(payload) = &suggestion.payload {
+                command.arg("PAYLOAD").arg(payload);
+            }
+            let _: i64 = command.query_async(&mut *con).await?;
+        }
+
+        let size: i64 = redis::cmd("FT.SUGLEN")
+            .arg(&dictionary_key)
+            .query_async(&mut *con)
+            .await?;
+        Ok(json!({"status": "success", "added": req.suggestions.len(), "size": size}))
+    }
+
+    pub async fn delete_suggestions(
+        &self,
+        req: SuggestionDeleteRequest,
+    ) -> Result<Value, Box<dyn Error>> {
+        let dictionary_key = suggestion_key(&req.index, &req.dictionary)?;
+        let refs_key = suggestion_refs_key(&dictionary_key);
+
+        let mut con = self.pool.get().await?;
+        let mut deleted = 0;
+        for string in &req.strings {
+            let removed: i64 = redis::cmd("FT.SUGDEL")
+                .arg(&dictionary_key)
+                .arg(string)
+                .query_async(&mut *con)
+                .await?;
+            let _: i64 = redis::cmd("HDEL")
+                .arg(&refs_key)
+                .arg(string)
+                .query_async(&mut *con)
+                .await?;
+            deleted += removed;
+        }
+
+        Ok(json!({"status": "success", "deleted": deleted}))
+    }
+
+    /// Loads the index definitions stored in the registry by `index`.
+    async fn index_definitions(&self) -> Result<Vec<IndexRequest>, Box<dyn Error>> {
+        Ok(self
+            .registry_entries(None)
+            .await?
+            .into_iter()
+            .map(|entry| entry.definition)
+            .collect())
+    }
+
+    /// Computes the vector fields declared with `source_fields` for the records stored under
+    /// the index prefixes, skipping records that already carry a vector. Returns the errors of
+    /// each record, in order; a failed embedding request fails every record it carried.
+    async fn embed_records(
+        &self,
+        definitions: &[IndexRequest],
+        records: &mut [(String, Value)],
+    ) -> Vec<Vec<RecordError>> {
+        let mut errors: Vec<Vec<RecordError>> = records.iter().map(|_| Vec::new()).collect();
+
+        for definition in definitions {
+            for field in &definition.schema {
+                let (vector, source_fields) = match &field.vector {
+                    Some(vector) => match &vector.source_fields {
+                        Some(source_fields) if !source_fields.is_empty() => (vector, source_fields),
+                        _ => continue,
+                    },
+                    None => continue,
+                };
+                let path = field_path(field);
+
+                let targets: Vec<(usize, String)> = records
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (key, record))| {
+                        definition
+                            .prefixes
+                            .iter()
+                            .any(|p| key.starts_with(p.as_str()))
+                            && get_json_path(record, path).is_none()
+                    })
+                    .filter_map(|(i, (_, record))| {
+                        let text = source_fields
+                            .iter()
+                            .filter_map(|f| get_json_path(record, f).and_then(Value::as_str))
+                            .collect::<Vec<&str>>()
+                            .join("\n");
+                        (!text.trim().is_empty()).then_some((i, text))
+                    })
+                    .collect();
+                if targets.is_empty() {
+                    continue;
+                }
+
+                let error = |message: String| RecordError {
+                    index: definition.index_name.clone(),
+                    field: field_names(field).1.to_string(),
+                    error: message,
+                };
+
+                let texts: Vec<String> = targets.iter().map(|(_, text)| text.clone()).collect();
+                let vectors = match &self.embedding_service {
+                    Some(embedding_service) => embedding_service
+                        .embed(&texts)
+                        .await
+                        .map_err(|e| format!("embedding failed: {}", e)),
+                    None => Err("embedding failed: EMBEDDING_URL is not configured".to_string()),
+                };
+                let vectors = match vectors {
+                    Ok(vectors) => vectors,
+                    Err(message) => {
+                        warn!(
+                            "Could not embed '{}' for index '{}': {}",
+                            field.field_name, definition.index_name, message
+                        );
+                        for (i, _) in &targets {
+                            errors[*i].push(error(message.clone()));
+                        }
+                        continue;
+                    }
+                };
+
+                for ((i, _), embedding) in targets.iter().zip(vectors) {
+                    if embedding.len() != vector.dim as usize {
+                        errors[*i].push(error(format!(
+                            "embedding has {} dimensions, expected {}",
+                            embedding.len(),
+                            vector.dim
+                        )));
+                        continue;
+                    }
+                    set_json_path(&mut records[*i].1, path, json!(embedding));
+                }
+            }
+        }
+
+        errors
+    }
+
+    pub async fn search(&self, mut req: SearchRequest) -> Result<Value, Box<dyn Error>> {
+        let spellcheck = req.spellcheck.take();
+        let index_name = req.index.clone();
+        let q = req.q.clone().unwrap_or_default();
+
+        let mut response = self.search_documents(req).await?;
+
+        // Cursor pages carry no totals and are never checked
+        if let (Some(options), Some(index_name)) = (spellcheck, index_name) {
+            let totals = response.get("totals").and_then(Value::as_u64);
+            if totals.is_some_and(|totals| options.always || totals == 0) {
+                response["spellcheck"] = self.spellcheck(&index_name, &q, &options).await?;
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Runs FT.SPELLCHECK on the free text of a query and rewrites it with the best
+    /// suggestion of each misspelled term.
+    async fn spellcheck(
+        &self,
+        index_name: &str,
+        q: &str,
+        options: &SpellcheckOptions,
+    ) -> Result<Value, Box<dyn Error>> {
+        let text_query = build_query(Some(q), None, &[], &[])?;
+        if text_query == "*" {
+            return Ok(json!({"suggestions": {}, "corrected_query": null}));
+        }
+
+        let mut command_args = vec![index_name.to_string(), text_query];
+        command_args.extend(compile_spellcheck_args(index_name, options)?);
+        command_args.extend(["DIALECT".to_string(), "2".to_string()]);
+
+        debug!(
+            "Executing Redis command: FT.SPELLCHECK {}",
+            command_args.join(" ")
+        );
+
+        let mut con = self.pool.get().await?;
+        let reply: Vec<redis::Value> = redis::cmd("FT.SPELLCHECK")
+            .arg(&command_args)
+            .query_async(&mut *con)
+            .await?;
+
+        let suggestions = spellcheck_suggestions(&reply);
+        let corrections = suggestions
+            .iter()
+            .filter_map(|(term, candidates)| {
+                let best = candidates
+                    .as_array()?
+                    .first()?
+                    .get("suggestion")?
+                    .as_str()?;
+                Some((term.clone(), best.to_string()))
+            })
+            .collect::<Vec<(String, String)>>();
+        let corrected_query = if corrections.is_empty() {
+            None
+        } else {
+            Some(rewrite_query(q, &corrections))
+        };
+
+        Ok(json!({
+            "suggestions": suggestions.into_iter().collect::<Map<String, Value>>(),
+            "corrected_query": corrected_query
+        }))
+    }
+
+    async fn search_documents(&self, req: SearchRequest) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        // A cursor already carries its index and query, only the page size can change
+        if let Some(cursor) = &req.cursor {
+            return self.read_cursor(cursor, req.limit).await;
+        }
+
+        let index_name = req.index.clone().ok_or("The 'index' field is required.")?;
+
+        // Vector searches rank by similarity, a distance order would silently replace it
+        if req.geo_sort.is_some() && (req.knn.is_some() || req.hybrid.is_some()) {
+            return Err(
+                ValidationError::new("geo_sort can't be combined with knn or hybrid").into(),
+            );
+        }
+        let sorted =
+            req.sort.as_ref().is_some_and(|keys| !keys.is_empty()) || req.sort_by.is_some();
+        if sorted && (req.knn.is_some() || req.hybrid.is_some()) {
+            return Err(ValidationError::new(
+                "sort and sort_by can't be combined with knn or hybrid",
+            )
+            .into());
+        }
+
+        // Compile the free-text query and filters into escaped RediSearch syntax
+        let (query_str, mut params) = compile_search_query(&req, req.q.as_deref())?;
+
+        if req.scroll.unwrap_or(false) {
+            // Cursor pages come straight from FT.AGGREGATE, unranked and unformatted
+            if sorted
+                || req.geo_sort.is_some()
+                || req.knn.is_some()
+                || req.hybrid.is_some()
+                || req.highlight.is_some()
+                || req.summarize.is_some()
+            {
+                return Err(ValidationError::new(
+                    "scroll can't be combined with sort, sort_by, geo_sort, knn, hybrid, highlight or summarize",
+                )
+                .into());
+            }
+            return self.open_cursor(req, index_name, query_str, params).await;
+        }
+
+        if req.hybrid.is_some() {
+            return self.search_hybrid(req, index_name, query_str, params).await;
+        }
+
+        // Distances are computed with APPLY, so geo sorting always goes through FT.AGGREGATE;
+        // explicit sort keys only break ties there
+        if req.geo_sort.is_some() {
+            let sort_keys = if req.sort.is_some() || req.sort_by.is_some() {
+                self.resolve_sort_keys(&req, &index_name).await?
+            } else {
+                Vec::new()
+            };
+            return self
+                .search_sorted(req, index_name, query_str, params, sort_keys)
+                .await;
+        }
+
+        // KNN hits are ranked by vector distance instead of the requested sort fields
+        let (query_str, sort_keys) = match &req.knn {
+            Some(knn) => {
+                params.push((
+                    "BLOB".to_string(),
+                    vector_blob(&knn.vector, knn.data_type.as_deref())?,
+                ));
+                (
+                    compile_knn_query(
+                        &query_str,
+                        knn,
+                        req.offset.unwrap_or(0) + req.limit.unwrap_or(10),
+                    )?,
+                    vec![SortKey {
+                        field: "__distance".to_string(),
+                        order: Some("ASC".to_string()),
+                    }],
+                )
+            }
+            None => {
+                let sort_keys = self.resolve_sort_keys(&req, &index_name).await?;
+                (query_str, sort_keys)
+            }
+        };
+        if sort_keys.len() > 1 {
+            return self
+                .search_sorted(req, index_name, query_str, params, sort_keys)
+                .await;
+        }
+
+        let query = req.q.clone().unwrap_or_else(|| "*".to_string());
+        let offset = req.offset.unwrap_or(0); // Convert to string for command args
+        let limit = req.limit.unwrap_or(10); // Convert to string for command args
+        let language = req.language.unwrap_or_else(|| "chinese".to_string());
+
+        let offset_str = offset.to_string();
+        let limit_str = limit.to_string();
+
+        let mut command_args = vec![
+            index_name.clone(),
+            query_str.clone(),
+            "LIMIT".to_string(),
+            offset_str,
+            limit_str,
+            "LANGUAGE".to_string(),
+            language,
+            "WITHSCORES".to_string(),
+        ];
+
+        if let Some(sort_key) = sort_keys.first() {
+            command_args.push("SORTBY".to_string());
+            command_args.push(sort_key.field.clone());
+            command_args.push(sort_order(sort_key.order.as_deref())?.to_string());
+        }
+
+        if let Some(scorer) = &req.scorer {
+            command_args.push("SCORER".to_string());
+            command_args.push(validate_scorer(scorer)?);
+        }
+        let explain_score = req.explain_score.unwrap_or(false);
+        if explain_score {
+            command_args.push("EXPLAINSCORE".to_string());
+        }
+
+        // Highlighted and summarized fields must be returned explicitly next to the document,
+        // or next to the projected fields when the caller restricts the response
+        let snippet_args = compile_snippet_args(req.highlight.as_ref(), req.summarize.as_ref())?;
+        let snippet_fields = snippet_args.fields;
+        let return_fields = req.return_fields.unwrap_or_default();
+        let projected_fields = return_fields
+            .iter()
+            .map(|f| f.alias.clone().unwrap_or_else(|| f.path.clone()))
+            .collect::<Vec<String>>();
+        if !return_fields.is_empty() || !snippet_fields.is_empty() {
+            let mut return_args = compile_return_fields(&return_fields)?;
+            if return_fields.is_empty() {
+                return_args.push("$".to_string());
+            }
+            for field in &snippet_fields {
+                if !projected_fields.contains(field) {
+                    return_args.push(field.clone());
+                }
+            }
+            if req.knn.is_some() {
+                return_args.push("__distance".to_string());
+            }
+            command_args.push("RETURN".to_string());
+            command_args.push(return_args.len().to_string());
+            command_args.extend(return_args);
+            command_args.extend(snippet_args.args);
+        }
+
+        // Query parameters (vectors, polygons) require dialect 2
+        if !params.is_empty() {
+            command_args.extend(["DIALECT".to_string(), "2".to_string()]);
+        }
+        let raw_search_results = self.run_search(command_args, params).await?;
+
+        // Extract total_hits from the first element of the response
+        let total_hits = match raw_search_results.first() {
+            Some(redis::Value::Int(total)) => *total as u32,
+            _ => 0,
+        };
+
+        let documents = search_hits(
+            &raw_search_results,
+            &projected_fields,
+            &snippet_fields,
+            explain_score,
+        );
+
+        let processing_time_ms = process_start_time.elapsed().as_millis();
+        let page = offset / limit + 1;
+        let total_pages = total_hits.div_ceil(limit as u32);
+
+        let response = json!({
+            "data": documents,
+            "query": &query,
+            "compiled_query": &query_str,
+            "totals": total_hits,
+            "processing_time_ms": processing_time_ms,
+            "limit": limit,
+            "offset": offset,
+            "page": page,
+            "totalPages": total_pages
+        });
+
+        Ok(response)
+    }
+
+    /// Runs an FT.SEARCH command, binding `params` as query parameters (queries using them
+    /// also need `DIALECT 2` among the arguments).
+    async fn run_search(
+        &self,
+        command_args: Vec<String>,
+        params: QueryParams,
+    ) -> Result<Vec<redis::Value>, Box<dyn Error>> {
+        let mut command = redis::cmd("FT.SEARCH");
+        command.arg(&command_args);
+        push_params(&mut command, &params);
+
+        // Log the command for debugging
+        debug!(
+            "Executing Redis command: FT.SEARCH {}{}",
+            command_args.join(" "),
+            describe_params(&params)
+        );
+
+        // Obtain a connection from the pool
+        let mut con = self.pool.get().await?;
+        let raw_search_results: Vec<redis::Value> = command.query_async(&mut *con).await?;
+
+        Ok(raw_search_results)
+    }
+
+    /// Runs the lexical query and the KNN query side by side and fuses both rankings.
+    async fn search_hybrid(
+        &self,
+        req: SearchRequest,
+        index_name: String,
+        text_query: String,
+        text_params: QueryParams,
+    ) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        let options = req.hybrid.clone().unwrap_or_default();
+        let knn = req
+            .knn
+            .clone()
+            .ok_or_else(|| ValidationError::new("Hybrid search requires a 'knn' query"))?;
+        let query = req.q.clone().unwrap_or_default();
+        if query.trim().is_empty() || query.trim() == "*" {
+            return Err(ValidationError::new("Hybrid search requires a text query 'q'").into());
+        }
+
+        let offset = req.offset.unwrap_or(0);
+        let limit = req.limit.unwrap_or(10);
+        let window = options.window.unwrap_or((offset + limit).max(50));
+        let language = req
+            .language
+            .clone()
+            .unwrap_or_else(|| "chinese".to_string());
+
+        let return_fields = req.return_fields.as_deref().unwrap_or_default();
+        let projected_fields = return_fields
+            .iter()
+            .map(|f| f.alias.clone().unwrap_or_else(|| f.path.clone()))
+            .collect::<Vec<String>>();
+        let mut return_args = compile_return_fields(return_fields)?;
+        if return_fields.is_empty() {
+            return_args.push("$".to_string());
+        }
+
+        // Lexical leg: the full query, ranked by the text scorer
+        let mut text_args = vec![
+            index_name.clone(),
+            text_query.clone(),
+            "LIMIT".to_string(),
+            "0".to_string(),
+            window.to_string(),
+            "LANGUAGE".to_string(),
+            language.clone(),
+            "WITHSCORES".to_string(),
+        ];
+        if let Some(scorer) = &req.scorer {
+            text_args.push("SCORER".to_string());
+            text_args.push(validate_scorer(scorer)?);
+        }
+        text_args.push("RETURN".to_string());
+        text_args.push(return_args.len().to_string());
+        text_args.extend(return_args.iter().cloned());
+        if !text_params.is_empty() {
+            text_args.extend(["DIALECT".to_string(), "2".to_string()]);
+        }
+
+        // Vector leg: the filters without the free text act as the KNN prefilter
+        let (prefilter, mut vector_params) = compile_search_query(&req, None)?;
+        let vector_query = compile_knn_query(&prefilter, &knn, window)?;
+        vector_params.push((
+            "BLOB".to_string(),
+            vector_blob(&knn.vector, knn.data_type.as_deref())?,
+        ));
+        let mut vector_args = vec![
+            index_name,
+            vector_query.clone(),
+            "LIMIT".to_string(),
+            "0".to_string(),
+            window.to_string(),
+            "LANGUAGE".to_string(),
+            language,
+            "WITHSCORES".to_string(),
+            "SORTBY".to_string(),
+            "__distance".to_string(),
+            "ASC".to_string(),
+            "RETURN".to_string(),
+            (return_args.len() + 1).to_string(),
+        ];
+        vector_args.extend(return_args);
+        vector_args.extend([
+            "__distance".to_string(),
+            "DIALECT".to_string(),
+            "2".to_string(),
+        ]);
+
+        let (text_results, vector_results) = tokio::try_join!(
+            self.run_search(text_args, text_params),
+            self.run_search(vector_args, vector_params)
+        )?;
+
+        let text_hits = search_hits(&text_results, &projected_fields, &[], false);
+        let vector_hits = search_hits(&vector_results, &projected_fields, &[], false);
+        let fused = fuse_hits(text_hits, vector_hits, &options)?;
+        // Only the fused candidates can be paged through; `totals` keeps the lexical count
+        let text_total = match text_results.first() {
+            Some(redis::Value::Int(total)) => *total as u32,
+            _ => 0,
+        };
+        let candidates = fused.len() as u32;
+
+        let documents = fused
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect::<Vec<Value>>();
+
+        Ok(json!({
+            "data": documents,
+            "query": &query,
+            "compiled_query": &text_query,
+            "vector_query": &vector_query,
+            "totals": text_total,
+            "candidates": candidates,
+            "processing_time_ms": process_start_time.elapsed().as_millis(),
+            "limit": limit,
+            "offset": offset,
+            "page": offset / limit + 1,
+            "totalPages": candidates.div_ceil(limit as u32)
+        }))
+    }
+
+    /// Resolves the requested sort keys and checks them against the SORTABLE attributes of
+    /// the index. The default `post_timestamp DESC` is dropped if that field is not sortable;
+    /// it is checked against cached attributes so searches without a sort skip FT.INFO.
+    async fn resolve_sort_keys(
+        &self,
+        req: &SearchRequest,
+        index_name: &str,
+    ) -> Result<Vec<SortKey>, Box<dyn Error>> {
+        let requested = match &req.sort {
+            Some(keys) if !keys.is_empty() => Some(keys.clone()),
+            _ => req.sort_by.as_ref().map(|field| {
+                vec![SortKey {
+                    field: field.clone(),
+                    order: req.sort_order.clone(),
+                }]
+            }),
+        };
+
+        let sortable = match requested {
+            Some(_) => self.sortable_fields(index_name).await?,
+            None => self.cached_sortable_fields(index_name).await?,
+        };
+
+        match requested {
+            Some(keys) => {
+                for key in &keys {
+                    validate_field_name(&key.field)?;
+                    sort_order(key.order.as_deref())?;
+                    if !sortable.contains(&key.field) {
+                        return Err(ValidationError::new(format!(
+                            "Field '{}' is not SORTABLE in index '{}' (sortable fields: {})",
+                            key.field,
+                            index_name,
+                            sortable.join(", ")
+                        ))
+                        .into());
+                    }
+                }
+                Ok(keys)
+            }
+            None if sortable.iter().any(|f| f == "post_timestamp") => Ok(vec![SortKey {
+                field: "post_timestamp".to_string(),
+                order: Some("DESC".to_string()),
+            }]),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Lists the attributes declared SORTABLE, read from FT.INFO, and refreshes the cache.
+    async fn sortable_fields(&self, index_name: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let info = self.index_info(index_name).await?;
+        let sortable: Vec<String> = info
+            .attributes
+            .into_iter()
+            .filter(|attribute| attribute.has_flag("SORTABLE"))
+            .map(|attribute| attribute.attribute)
+            .collect();
+
+        self.sortable_cache
+            .lock()
+            .unwrap()
+            .insert(index_name.to_string(), (Instant::now(), sortable.clone()));
+        Ok(sortable)
+    }
+
+    /// Like `sortable_fields`, reusing a list read less than `SORTABLE_CACHE_TTL` ago.
+    async fn cached_sortable_fields(
+        &self,
+        index_name: &str,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let cached = self
+            .sortable_cache
+            .lock()
+            .unwrap()
+            .get(index_name)
+            .filter(|(read_at, _)| read_at.elapsed() < SORTABLE_CACHE_TTL)
+            .map(|(_, sortable)| sortable.clone());
+
+        match cached {
+            Some(sortable) => Ok(sortable),
+            None => self.sortable_fields(index_name).await,
+        }
+    }
+
+    /// Forgets the cached SORTABLE attributes of an index whose schema changed.
+    fn invalidate_sortable_fields(&self, index_name: &str) {
+        self.sortable_cache.lock().unwrap().remove(index_name);
+    }
+
+    /// Runs a search sorted by several keys through FT.AGGREGATE, since FT.SEARCH only
+    /// accepts a single SORTBY field. A `geo_sort` becomes the first key, computed with APPLY.
+    async fn search_sorted(
+        &self,
+        req: SearchRequest,
+        index_name: String,
+        query_str: String,
+        params: QueryParams,
+        mut sort_keys: Vec<SortKey>,
+    ) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        if req.highlight.is_some() || req.summarize.is_some() {
+            return Err(ValidationError::new(
+                "Highlight and summarize are not available when sorting by several fields",
+            )
+            .into());
+        }
+        // FT.AGGREGATE does not score documents
+        if req.scorer.is_some() || req.explain_score.unwrap_or(false) {
+            return Err(ValidationError::new(
+                "Scorer and explain_score are not available when sorting by several fields",
+            )
+            .into());
+        }
+
+        let query = req.q.clone().unwrap_or_else(|| "*".to_string());
+        let offset = req.offset.unwrap_or(0);
+        let limit = req.limit.unwrap_or(10);
+        let language = req.language.unwrap_or_else(|| "chinese".to_string());
+        let mut load_args = compile_load_args(&req.return_fields.unwrap_or_default())?;
+
+        let mut apply_args = Vec::new();
+        if let Some(geo_sort) = &req.geo_sort {
+            apply_args.extend([
+                "APPLY".to_string(),
+                geo_distance_expression(geo_sort)?,
+                "AS".to_string(),
+                "__geo_distance".to_string(),
+            ]);
+            load_args.push(format!("@{}", geo_sort.field));
+            sort_keys.insert(
+                0,
+                SortKey {
+                    field: "__geo_distance".to_string(),
+                    order: Some("ASC".to_string()),
+                },
+            );
+        }
+
+        let mut command_args = vec![
+            index_name,
+            query_str.clone(),
+            "LANGUAGE".to_string(),
+            language,
+            "LOAD".to_string(),
+            load_args.len().to_string(),
+        ];
+        command_args.extend(load_args);
+        command_args.extend(apply_args);
+        command_args.extend(compile_sort_keys(&sort_keys)?);
+        command_args.extend(["LIMIT".to_string(), offset.to_string(), limit.to_string()]);
+        if !params.is_empty() {
+            command_args.extend(["DIALECT".to_string(), "2".to_string()]);
+        }
+
+        debug!(
+            "Executing Redis command: FT.AGGREGATE {}{}",
+            command_args.join(" "),
+            describe_params(&params)
+        );
+
+        let mut command = redis::cmd("FT.AGGREGATE");
+        command.arg(&command_args);
+        push_params(&mut command, &params);
+
+        let mut con = self.pool.get().await?;
+        let raw_results: Vec<redis::Value> = command.query_async(&mut *con).await?;
+
+        let total_hits = match raw_results.first() {
+            Some(redis::Value::Int(total)) => *total as u32,
+            _ => 0,
+        };
+        let documents = raw_results
+            .iter()
+            .skip(1)
+            .filter_map(aggregate_row_document)
+            .collect::<Vec<Value>>();
+
+        Ok(json!({
+            "data": documents,
+            "query": &query,
+            "compiled_query": &query_str,
+            "totals": total_hits,
+            "processing_time_ms": process_start_time.elapsed().as_millis(),
+            "limit": limit,
+            "offset": offset,
+            "page": offset / limit + 1,
+            "totalPages": total_hits.div_ceil(limit as u32)
+        }))
+    }
+
+    /// Opens an FT.AGGREGATE cursor over the matching documents and returns the first page.
+    async fn open_cursor(
+        &self,
+        req: SearchRequest,
+        index_name: String,
+        query_str: String,
+        params: QueryParams,
+    ) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        let page_size = req.limit.unwrap_or(1000);
+        let language = req.language.unwrap_or_else(|| "chinese".to_string());
+        let return_fields = req.return_fields.unwrap_or_default();
+
+        let load_args = compile_load_args(&return_fields)?;
+
+        let mut command_args = vec![
+            index_name.clone(),
+            query_str.clone(),
+            "LANGUAGE".to_string(),
+            language,
+            "LOAD".to_string(),
+            load_args.len().to_string(),
+        ];
+        command_args.extend(load_args);
+        command_args.extend([
+            "WITHCURSOR".to_string(),
+            "COUNT".to_string(),
+            page_size.to_string(),
+            "MAXIDLE".to_string(),
+            req.max_idle_ms.unwrap_or(300000).to_string(),
+        ]);
+        if !params.is_empty() {
+            command_args.extend(["DIALECT".to_string(), "2".to_string()]);
+        }
+
+        debug!(
+            "Executing Redis command: FT.AGGREGATE {}{}",
+            command_args.join(" "),
+            describe_params(&params)
+        );
+
+        let mut command = redis::cmd("FT.AGGREGATE");
+        command.arg(&command_args);
+        push_params(&mut command, &params);
+
+        let mut con = self.pool.get().await?;
+        let (rows, cursor_id): (Vec<redis::Value>, i64) = command.query_async(&mut *con).await?;
+
+        Ok(cursor_page(
+            &index_name,
+            &rows,
+            cursor_id,
+            page_size,
+            process_start_time,
+        ))
+    }
+
+    /// Reads the next page of a cursor opened by `open_cursor`.
+    async fn read_cursor(&self, token: &str, limit: Option<i64>) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        let (index_name, cursor_id) = decode_cursor(token)?;
+        let page_size = limit.unwrap_or(1000);
+
+        debug!(
+            "Executing Redis command: FT.CURSOR READ {} {} COUNT {}",
+            index_name, cursor_id, page_size
+        );
+
+        let mut con = self.pool.get().await?;
+        let response: Result<(Vec<redis::Value>, i64), redis::RedisError> = redis::cmd("FT.CURSOR")
+            .arg("READ")
+            .arg(&index_name)
+            .arg(cursor_id)
+            .arg("COUNT")
+            .arg(page_size)
+            .query_async(&mut *con)
+            .await;
+
+        let (rows, next_cursor_id) = match response {
+            Ok(page) => page,
+            Err(e) if is_missing_cursor(&e) => {
+                return Err(ValidationError::new("Cursor has expired or was fully read").into())
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(cursor_page(
+            &index_name,
+            &rows,
+            next_cursor_id,
+            page_size,
+            process_start_time,
+        ))
+    }
+
+    /// Deletes a cursor that will not be read to the end, instead of leaving it to MAXIDLE.
+    pub async fn close_cursor(&self, token: &str) -> Result<(), Box<dyn Error>> {
+        let (index_name, cursor_id) = decode_cursor(token)?;
+
+        let mut con = self.pool.get().await?;
+        let response: Result<String, redis::RedisError> = redis::cmd("FT.CURSOR")
+            .arg("DEL")
+            .arg(&index_name)
+            .arg(cursor_id)
+            .query_async(&mut *con)
+            .await;
+
+        match response {
+            Ok(_) => Ok(()),
+            Err(e) if is_missing_cursor(&e) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn aggregate(&self, req: AggregateRequest) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        let index_name = req.index.ok_or("The 'index' field is required.")?;
+        let query_str = build_query(
+            req.q.as_deref(),
+            req.filter.as_ref(),
+            &req.numeric_filters.unwrap_or_default(),
+            &req.tag_filters.unwrap_or_default(),
+        )?;
+        let language = req.language.unwrap_or_else(|| "chinese".to_string());
+        let group_by = req.group_by.unwrap_or_default();
+        let reducers = req.reducers.unwrap_or_else(|| {
+            vec![Reducer {
+                function: "COUNT".to_string(),
+                field: None,
+                alias: None,
+            }]
+        });
+        let apply_steps = req.apply.unwrap_or_default();
+        let offset = req.offset.unwrap_or(0);
+        let limit = req.limit.unwrap_or(100);
+
+        let mut command_args = vec![
+            index_name.clone(),
+            query_str.clone(),
+            "LANGUAGE".to_string(),
+            language,
+        ];
+
+        // Load the attributes used by the pipeline so non-sortable fields can be grouped too
+        let apply_aliases: Vec<&str> = apply_steps.iter().map(|s| s.alias.as_str()).collect();
+        let extra_load = req.load.unwrap_or_default();
+        let mut load_fields: Vec<&str> = Vec::new();
+        for field in extra_load
+            .iter()
+            .chain(group_by.iter())
+            .chain(reducers.iter().filter_map(|r| r.field.as_ref()))
+        {
+            validate_field_name(field)?;
+            if !apply_aliases.contains(&field.as_str()) && !load_fields.contains(&field.as_str()) {
+                load_fields.push(field);
+            }
+        }
+        // Fields read by an APPLY, unless an earlier step computed them
+        for (i, step) in apply_steps.iter().enumerate() {
+            for field in expression_fields(&step.expression) {
+                if !apply_aliases[..i].contains(&field) && !load_fields.contains(&field) {
+                    load_fields.push(field);
+                }
+            }
+        }
+        if !load_fields.is_empty() {
+            command_args.push("LOAD".to_string());
+            command_args.push(load_fields.len().to_string());
+            command_args.extend(load_fields.iter().map(|f| format!("@{}", f)));
+        }
+
+        for step in &apply_steps {
+            validate_field_name(&step.alias)?;
+            command_args.extend([
+                "APPLY".to_string(),
+                step.expression.clone(),
+                "AS".to_string(),
+                step.alias.clone(),
+            ]);
+        }
+
+        command_args.push("GROUPBY".to_string());
+        command_args.push(group_by.len().to_string());
+        command_args.extend(group_by.iter().map(|f| format!("@{}", f)));
+
+        // Reducer outputs (and APPLY results) are numeric, everything else stays a string
+        let mut numeric_fields: Vec<String> = apply_aliases.iter().map(|a| a.to_string()).collect();
+        for reducer in &reducers {
+            let (reduce_args, alias) = compile_reducer(reducer)?;
+            command_args.extend(reduce_args);
+            if !reducer.function.eq_ignore_ascii_case("TOLIST") {
+                numeric_fields.push(alias);
+            }
+        }
+
+        for expression in req.filters.unwrap_or_default() {
+            command_args.push("FILTER".to_string());
+            command_args.push(expression);
+        }
+
+        if let Some(sort_keys) = req.sort_by.filter(|keys| !keys.is_empty()) {
+            command_args.extend(compile_sort_keys(&sort_keys)?);
+        }
+
+        command_args.extend(["LIMIT".to_string(), offset.to_string(), limit.to_string()]);
+
+        debug!(
+            "Executing Redis command: FT.AGGREGATE {}",
+            command_args.join(" ")
+        );
+
+        let mut con = self.pool.get().await?;
+        let raw_results: Vec<redis::Value> = redis::cmd("FT.AGGREGATE")
+            .arg(&command_args)
+            .query_async(&mut *con)
+            .await?;
+
+        let total = match raw_results.first() {
+            Some(redis::Value::Int(total)) => *total,
+            _ => 0,
+        };
+
+        let buckets = raw_results
+            .iter()
+            .skip(1)
+            .filter_map(|row| match row {
+                redis::Value::Bulk(items) => Some(
+                    field_pairs(items)
+                        .into_iter()
+                        .map(|(name, value)| {
+                            let value = if numeric_fields.contains(&name) {
+                                value_to_typed_json(value)
+                            } else {
+                                value_to_json(value)
+                            };
+                            (name, value)
+                        })
+                        .collect::<Map<String, Value>>(),
+                ),
+                _ => None,
+            })
+            .collect::<Vec<Map<String, Value>>>();
+
+        Ok(json!({
+            "buckets": buckets,
+            "query": &query_str,
+            "totals": total,
+            "processing_time_ms": process_start_time.elapsed().as_millis(),
+            "limit": limit,
+            "offset": offset
+        }))
+    }
+
+    pub async fn histogram(&self, req: HistogramRequest) -> Result<Value, Box<dyn Error>> {
+        let process_start_time = Instant::now();
+
+        let field = req.field.unwrap_or_else(|| "post_timestamp".to_string());
+        validate_field_name(&field)?;
+
+        let timezone_offset = req.timezone.unwrap_or(8);
+        if !(-12..=14).contains(&timezone_offset) {
+            return Err(ValidationError::new("timezone must be between -12 and 14 hours").into());
+        }
+
+        let interval_name = req.interval.unwrap_or_else(|| "day".to_string());
+        let interval = parse_interval(&interval_name)?;
+
+        let start_time_str = req
+            .start_time
+            .ok_or_else(|| ValidationError::new("The 'start_time' field is required."))?;
+        let end_time_str = req
+            .end_time
+            .ok_or_else(|| ValidationError::new("The 'end_time' field is required."))?;
+        let start_time =
+            parse_date_time_with_timezone(&start_time_str, timezone_offset)?.timestamp();
+        let end_time = parse_date_time_with_timezone(&end_time_str, timezone_offset)?.timestamp();
+        if start_time > end_time {
+            return Err(ValidationError::new("start_time must not be after end_time").into());
+        }
+
+        // Enumerate every bucket up front so empty ones can be zero-filled
+        let mut bucket_keys = Vec::new();
+        let mut bucket = bucket_start(start_time, interval, timezone_offset);
+        while bucket <= end_time {
+            if bucket_keys.len() >= MAX_HISTOGRAM_BUCKETS {
+                return Err(ValidationError::new(format!(
+                    "Histogram would exceed {} buckets, use a wider interval",
+                    MAX_HISTOGRAM_BUCKETS
+                ))
+                .into());
+            }
+            bucket_keys.push(bucket);
+            bucket = next_bucket(bucket, interval, timezone_offset);
+        }
+
+        let mut numeric_filters = req.numeric_filters.unwrap_or_default();
+        numeric_filters.push(RangeFilter {
+            field: field.clone(),
+            gt: None,
+            gte: Some(start_time as f64),
+            lt: None,
+            lte: Some(end_time as f64),
+        });
+
+        // Year buckets are aggregated per month and folded together below
+        let months_per_bucket = if interval == Interval::Year { 12 } else { 1 };
+        let aggregate = self
+            .aggregate(AggregateRequest {
+                index: req.index,
+                q: req.q,
+                filter: req.filter,
+                numeric_filters: Some(numeric_filters),
+                tag_filters: req.tag_filters,
+                load: None,
+                group_by: Some(vec!["bucket".to_string()]),
+                reducers: None,
+                apply: Some(vec![ApplyStep {
+                    expression: histogram_expression(&field, interval, timezone_offset),
+                    alias: "bucket".to_string(),
+                }]),
+                filters: None,
+                sort_by: None,
+                offset: Some(0),
+                limit: Some((bucket_keys.len() * months_per_bucket + 1) as i64),
+                language: req.language,
+            })
+            .await?;
+
+        let mut counts: HashMap<i64, i64> = HashMap::new();
+        if let Some(buckets) = aggregate["buckets"].as_array() {
+            for row in buckets {
+                if let (Some(key), Some(count)) = (row["bucket"].as_f64(), row["count"].as_i64()) {
+                    let key = bucket_start(key as i64, interval, timezone_offset);
+                    *counts.entry(key).or_insert(0) += count;
+                }
+            }
+        }
+
+        let buckets = bucket_keys
+            .iter()
+            .map(|key| {
+                json!({
+                    "key": key,
+                    "key_as_string": format_with_timezone(*key, timezone_offset),
+                    "count": counts.get(key).copied().unwrap_or(0)
+                })
+            })
+            .collect::<Vec<Value>>();
+
+        Ok(json!({
+            "buckets": buckets,
+            "field": field,
+            "interval": interval_name,
+            "timezone": timezone_offset,
+            "totals": counts.values().sum::<i64>(),
+            "processing_time_ms": process_start_time.elapsed().as_millis()
+        }))
+    }
+
+    pub async fn delete(&self, req: DeleteRequest) -> Result<Value, Box<dyn Error>> {
+        if req.source.is_none()
+            && (req.keys.is_none() || req.keys.as_ref().map(|k| k.is_empty()).unwrap_or(true))
+        {
+            return Err("Either source or keys must be provided and not empty.".into());
+        }
+
+        // Deleted documents are read first when their strings have to leave the dictionaries
+        let definitions = self.index_definitions().await?;
+        let definitions = definitions
+            .into_iter()
+            .filter(|d| d.suggest.as_ref().is_some_and(|s| !s.is_empty()))
+            .collect::<Vec<IndexRequest>>();
+
+        let mut con = self.pool.get().await?;
+
+        // If a prefix is provided, delete keys matching the prefix.
+        if let Some(source) = &req.source {
+            let mut cursor = 0;
+            loop {
+                let (next_cursor, keys): (i64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(format!("{}:*", source))
+                    .arg("COUNT")
+                    .arg(1000) // Adjust count based on your use case
+                    .query_async(&mut *con)
+                    .await?;
+
+                if !keys.is_empty() {
+                    self.unlink_documents(&mut con, &definitions, keys).await?;
+                }
+
+                cursor = next_cursor;
+                if cursor == 0 {
+                    break;
+                }
+            }
+        }
+
+        // If specific keys are provided, delete them directly.
+        if let Some(keys) = &req.keys {
+            if !keys.is_empty() {
+                self.unlink_documents(&mut con, &definitions, keys.clone())
+                    .await?;
+            }
+        }
+
+        Ok(json!({"status": "success"}))
+    }
+
+    /// Unlinks documents, releasing their suggestions for the given index definitions.
+    async fn unlink_documents(
+        &self,
+        con: &mut redis::aio::Connection,
+        definitions: &[IndexRequest],
+        keys: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let records = if definitions.is_empty() {
+            Vec::new()
+        } else {
+            let documents: Vec<Option<String>> = redis::cmd("JSON.MGET")
+                .arg(&keys)
+                .arg("$")
+                .query_async(con)
+                .await?;
+            // `$` wraps each document in an array
+            keys.iter()
+                .zip(documents)
+                .filter_map(|(key, document)| {
+                    let document = serde_json::from_str::<Value>(&document?).ok()?;
+                    Some((key.clone(), document.get(0)?.clone()))
+                })
+                .collect::<Vec<(String, Value)>>()
+        };
+
+        // Using UNLINK instead of DEL for potentially non-blocking deletion in newer Redis versions
+        let _: () = redis::cmd("UNLINK").arg(keys).query_async(con).await?;
+
+        self.update_suggestions(con, definitions, &records, -1)
+            .await
+    }
+}
+
+/// Parses the version of a physical index named `<alias>_v<N>`.
+fn index_version(alias: &str, index_name: &str) -> Option<u32> {
+    index_name
+        .strip_prefix(alias)?
+        .strip_prefix("_v")?
+        .parse()
+        .ok()
+}
+
+fn synonyms_key(index: &str) -> String {
+    format!("{}{}", SYNONYMS_KEY_PREFIX, index)
+}
+
+/// JSONPath of one group in the synonyms document; the id is quoted as a JSON string.
+fn synonym_group_path(group_id: &str) -> String {
+    format!("$.groups[{}]", json!(group_id))
+}
+
+/// RediSearch replies `Cursor not found` to READ and DEL once a cursor expired or was
+/// exhausted; the first word of the reply is the error code.
+fn is_missing_cursor(e: &redis::RedisError) -> bool {
+    e.kind() == redis::ErrorKind::ExtensionError && e.code() == Some("Cursor")
+}
+
+fn is_unknown_index(e: &redis::RedisError) -> bool {
+    let message = e.to_string().to_lowercase();
+    message.contains("unknown index") || message.contains("no such index")
+}
+
+/// Compiles the query of a search request, including its `start_time`/`end_time` window and
+/// geo filters, along with the query parameters it binds.
+/// `q` is passed separately so the filters can be compiled without the free text.
+fn compile_search_query(
+    req: &SearchRequest,
+    q: Option<&str>,
+) -> Result<(String, QueryParams), Box<dyn Error>> {
+    let filter_date_field = req
+        .filter_date_by
+        .clone()
+        .unwrap_or_else(|| "post_timestamp".to_string());
+
+    // The date window is applied as one more numeric range next to the caller's filters
+    let mut numeric_filters = req.numeric_filters.clone().unwrap_or_default();
+    if let (Some(start_time_str), Some(end_time_str)) = (&req.start_time, &req.end_time) {
+        let start_time = parse_date_time_with_timezone(start_time_str, 8)?;
+        let end_time = parse_date_time_with_timezone(end_time_str, 8)?;
+
+        numeric_filters.push(RangeFilter {
+            field: filter_date_field,
+            gt: None,
+            gte: Some(start_time.timestamp() as f64),
+            lt: None,
+            lte: Some(end_time.timestamp() as f64),
+        });
+    }
+
+    let mut query = build_query(
+        q,
+        req.filter.as_ref(),
+        &numeric_filters,
+        req.tag_filters.as_deref().unwrap_or_default(),
+    )?;
+
+    // Polygons can't be written inline, each one is bound as a `$shapeN` parameter
+    let mut params = QueryParams::new();
+    for geo_filter in req.geo_filters.as_deref().unwrap_or_default() {
+        let clause = match geo_filter {
+            GeoFilter::Radius(radius) => compile_geo_radius(radius)?,
+            GeoFilter::Polygon(polygon) => {
+                let param = format!("shape{}", params.len());
+                let (clause, shape) = compile_geo_polygon(polygon, &param)?;
+                params.push((param, shape.into_bytes()));
+                clause
+            }
+        };
+        if query == "*" {
+            query = clause;
+        } else {
+            query = format!("{} {}", query, clause);
+        }
+    }
+
+    Ok((query, params))
+}
+
+/// Appends `PARAMS n name value ...` to a command.
+fn push_params(command: &mut redis::Cmd, params: &QueryParams) {
+    if params.is_empty() {
+        return;
+    }
+    command.arg("PARAMS").arg(params.len() * 2);
+    for (name, value) in params {
+        command.arg(name).arg(value.as_slice());
+    }
+}
+
+/// Renders the PARAMS of a command for debug logs, without the binary values.
+fn describe_params(params: &QueryParams) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let rendered = params
+        .iter()
+        .map(|(name, value)| match name.as_str() {
+            "BLOB" => format!("{} <{} bytes>", name, value.len()),
+            _ => format!("{} {}", name, String::from_utf8_lossy(value)),
+        })
+        .collect::<Vec<String>>();
+    format!(" PARAMS {} {}", params.len() * 2, rendered.join(" "))
+}
+
+/// LOAD arguments (without the leading count) fetching the key and either the whole
+/// document or the projected fields.
+fn compile_load_args(return_fields: &[ReturnField]) -> Result<Vec<String>, ValidationError> {
+    let mut load_args = vec!["@__key".to_string()];
+    if return_fields.is_empty() {
+        load_args.push("$".to_string());
+    } else {
+        load_args.extend(compile_return_fields(return_fields)?);
+    }
+    Ok(load_args)
+}
+
+/// Builds a document from an FT.AGGREGATE row loaded with `compile_load_args`.
+fn aggregate_row_document(row: &redis::Value) -> Option<Value> {
+    let items = match row {
+        redis::Value::Bulk(items) => items,
+        _ => return None,
+    };
+
+    let pairs = field_pairs(items);
+    let mut document = pairs
+        .iter()
+        .find(|(name, _)| name == "$")
+        .and_then(|(_, value)| value_to_string(value))
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    if let Value::Object(object) = &mut document {
+        for (name, value) in pairs {
+            match name.as_str() {
+                "$" => {}
+                "__key" => {
+                    object.insert("_key".to_string(), value_to_json(value));
+                }
+                "__geo_distance" => {
+                    object.insert("_geo_distance".to_string(), value_to_typed_json(value));
+                }
+                // Fields loaded only to compute a distance are already in the document
+                _ if object.contains_key(&name) => {}
+                _ => {
+                    object.insert(name, returned_value(value));
+                }
+            }
+        }
+    }
+    Some(document)
+}
+
+/// Builds a cursor page response; rows are `[name, value, ...]` lists from FT.AGGREGATE LOAD.
+fn cursor_page(
+    index_name: &str,
+    rows: &[redis::Value],
+    cursor_id: i64,
+    page_size: i64,
+    process_start_time: Instant,
+) -> Value {
+    let documents = rows
+        .iter()
+        .skip(1)
+        .filter_map(aggregate_row_document)
+        .collect::<Vec<Value>>();
+
+    // Redis returns cursor id 0 once the result set is exhausted
+    let cursor = if cursor_id == 0 {
+        None
+    } else {
+        Some(encode_cursor(index_name, cursor_id))
+    };
+
+    json!({
+        "data": documents,
+        "cursor": cursor,
+        "limit": page_size,
+        "processing_time_ms": process_start_time.elapsed().as_millis()
+    })
+}
+
+/// Parses an FT.SPELLCHECK reply, a list of `["TERM", term, [[score, suggestion], ...]]`
+/// entries, into the misspelled terms with their suggestions ordered by score.
+fn spellcheck_suggestions(reply: &[redis::Value]) -> Vec<(String, Value)> {
+    reply
+        .iter()
+        .filter_map(|entry| {
+            let items = match entry {
+                redis::Value::Bulk(items) => items,
+                _ => return None,
+            };
+            let term = value_to_string(items.get(1)?)?;
+            let candidates = match items.get(2) {
+                Some(redis::Value::Bulk(candidates)) => candidates
+                    .iter()
+                    .filter_map(|candidate| match candidate {
+                        redis::Value::Bulk(pair) => Some(json!({
+                            "suggestion": value_to_string(pair.get(1)?)?,
+                            "score": value_to_typed_json(pair.first()?)
+                        })),
+                        _ => None,
+                    })
+                    .collect::<Vec<Value>>(),
+                _ => Vec::new(),
+            };
+            Some((term, Value::Array(candidates)))
+        })
+        .collect()
+}
+
+/// Parses an FT.SEARCH WITHSCORES reply into documents carrying `_key` and `_score`.
+/// Hits come back as key, score (or [score, explanation]) and field list triples.
+fn search_hits(
+    raw_search_results: &[redis::Value],
+    projected_fields: &[String],
+    snippet_fields: &[String],
+    explain_score: bool,
+) -> Vec<Value> {
+    raw_search_results
+        .get(1..)
+        .unwrap_or_default()
+        .chunks(3)
+        .filter_map(|hit| match hit {
+            [key, score, redis::Value::Bulk(items)] => {
+                let mut document =
+                    build_document(field_pairs(items), projected_fields, snippet_fields)?;
+                if let Value::Object(object) = &mut document {
+                    object.insert("_key".to_string(), value_to_json(key));
+                    match score {
+                        redis::Value::Bulk(parts) if explain_score => {
+                            let score = parts.first().map(value_to_typed_json);
+                            object.insert("_score".to_string(), json!(score));
+                            let explanation = parts.get(1).map(value_to_json);
+                            object.insert("_explain".to_string(), json!(explanation));
+                        }
+                        _ => {
+                            object.insert("_score".to_string(), value_to_typed_json(score));
+                        }
+                    }
+                }
+                Some(document)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Builds a hit from its returned fields: the `$` payload is the document, or projected
+/// fields are assembled into one, and any highlighted or summarized fields are attached
+/// under `_highlights`. KNN distances are attached as `_distance`.
+fn build_document(
+    fields: Vec<(String, &redis::Value)>,
+    projected_fields: &[String],
+    snippet_fields: &[String],
+) -> Option<Value> {
+    let mut document = if projected_fields.is_empty() {
+        None
+    } else {
+        Some(Value::Object(Map::new()))
+    };
+    let mut highlights = Map::new();
+    let mut distance = None;
+
+    for (name, value) in fields {
+        if name == "$" {
+            document = match value {
+                redis::Value::Data(bytes) => serde_json::from_slice::<Value>(bytes).ok(),
+                _ => None,
+            };
+            continue;
+        }
+        if name == "__distance" {
+            distance = Some(value_to_typed_json(value));
+            continue;
+        }
+        if snippet_fields.contains(&name) {
+            highlights.insert(name.clone(), value_to_json(value));
+        }
+        if projected_fields.contains(&name) {
+            if let Some(Value::Object(object)) = &mut document {
+                object.insert(name, returned_value(value));
+            }
+        }
+    }
+
+    let mut document = document?;
+    if let Value::Object(object) = &mut document {
+        if !highlights.is_empty() {
+            object.insert("_highlights".to_string(), Value::Object(highlights));
+        }
+        if let Some(distance) = distance {
+            object.insert("_distance".to_string(), distance);
+        }
+    }
+
+    Some(document)
+}
+
+/// RETURN yields JSON values as text: strings come back bare, everything else serialized.
+/// A bare string can't be told apart from a serialized number or boolean, so scalars are
+/// kept as text and only serialized arrays and objects are parsed back.
+fn returned_value(value: &redis::Value) -> Value {
+    match value_to_json(value) {
+        Value::String(text) if text.starts_with(['[', '{']) => {
+            match serde_json::from_str::<Value>(&text) {
+                Ok(parsed @ (Value::Array(_) | Value::Object(_))) => parsed,
+                _ => Value::String(text),
+            }
+        }
+        other => other,
     }
 }
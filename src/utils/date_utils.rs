@@ -1,17 +1,237 @@
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use crate::models::validation_error::ValidationError;
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, TimeZone, Utc};
 
-pub fn parse_date_time_with_timezone(date_str: &str, timezone_offset: i32) -> DateTime<Utc> {
-    let naive_date_time = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S")
-        .expect("Failed to parse date and time");
+pub fn parse_date_time_with_timezone(
+    date_str: &str,
+    timezone_offset: i32,
+) -> Result<DateTime<Utc>, ValidationError> {
+    let naive_date_time =
+        NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S").map_err(|_| {
+            ValidationError::new(format!(
+                "Invalid date '{}', expected format YYYY-MM-DD HH:MM:SS",
+                date_str
+            ))
+        })?;
 
     // Convert timezone offset to seconds and explicitly convert it to i64
     let timezone_offset_seconds: i64 = (timezone_offset * 3600) as i64;
 
     // Adjust the naive date time with timezone offset
-    let adjusted_date_time = naive_date_time.timestamp() - timezone_offset_seconds;
+    let adjusted_date_time = naive_date_time.and_utc().timestamp() - timezone_offset_seconds;
 
     // Create DateTime<Utc> object
     Utc.timestamp_opt(adjusted_date_time, 0)
         .single()
-        .expect("Failed to create DateTime<Utc>")
+        .ok_or_else(|| ValidationError::new(format!("Date '{}' is out of range", date_str)))
+}
+
+/// Histogram bucket width. Fixed intervals are plain seconds, the others follow the calendar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interval {
+    Fixed(i64),
+    Week,
+    Month,
+    Year,
+}
+
+/// Parses `minute`, `hour`, `day`, `week`, `month`, `year` or a fixed width such as `15m`, `6h`, `2d`.
+pub fn parse_interval(interval: &str) -> Result<Interval, ValidationError> {
+    let interval = interval.trim().to_lowercase();
+    match interval.as_str() {
+        "minute" => return Ok(Interval::Fixed(60)),
+        "hour" => return Ok(Interval::Fixed(3600)),
+        "day" => return Ok(Interval::Fixed(86400)),
+        "week" => return Ok(Interval::Week),
+        "month" => return Ok(Interval::Month),
+        "year" => return Ok(Interval::Year),
+        _ => {}
+    }
+
+    let (count, unit_seconds) = match interval.char_indices().last() {
+        Some((i, 'm')) => (&interval[..i], 60),
+        Some((i, 'h')) => (&interval[..i], 3600),
+        Some((i, 'd')) => (&interval[..i], 86400),
+        _ => ("", 0),
+    };
+    match count.parse::<i64>() {
+        Ok(count) if count > 0 && unit_seconds > 0 => Ok(Interval::Fixed(count * unit_seconds)),
+        _ => Err(ValidationError::new(format!(
+            "Invalid interval '{}', expected minute, hour, day, week, month, year or e.g. 15m, 6h, 2d",
+            interval
+        ))),
+    }
+}
+
+/// Returns the start of the bucket containing `timestamp` in the given timezone offset (hours).
+pub fn bucket_start(timestamp: i64, interval: Interval, timezone_offset: i32) -> i64 {
+    let offset_seconds = (timezone_offset * 3600) as i64;
+    match interval {
+        Interval::Fixed(width) => {
+            (timestamp + offset_seconds).div_euclid(width) * width - offset_seconds
+        }
+        // 1970-01-05 was the first Monday after the epoch
+        Interval::Week => {
+            let monday = 4 * 86400;
+            (timestamp + offset_seconds - monday).div_euclid(604800) * 604800 + monday
+                - offset_seconds
+        }
+        Interval::Month | Interval::Year => {
+            let local = local_time(timestamp, timezone_offset);
+            let month = if interval == Interval::Month {
+                local.month()
+            } else {
+                1
+            };
+            calendar_start(local.year(), month, timezone_offset)
+        }
+    }
+}
+
+/// Returns the start of the bucket following the one starting at `bucket`.
+pub fn next_bucket(bucket: i64, interval: Interval, timezone_offset: i32) -> i64 {
+    match interval {
+        Interval::Fixed(width) => bucket + width,
+        Interval::Week => bucket + 604800,
+        Interval::Month => {
+            let local = local_time(bucket, timezone_offset);
+            if local.month() == 12 {
+                calendar_start(local.year() + 1, 1, timezone_offset)
+            } else {
+                calendar_start(local.year(), local.month() + 1, timezone_offset)
+            }
+        }
+        Interval::Year => calendar_start(
+            local_time(bucket, timezone_offset).year() + 1,
+            1,
+            timezone_offset,
+        ),
+    }
+}
+
+pub fn format_with_timezone(timestamp: i64, timezone_offset: i32) -> String {
+    local_time(timestamp, timezone_offset)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn timezone(timezone_offset: i32) -> FixedOffset {
+    FixedOffset::east_opt(timezone_offset * 3600)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+}
+
+fn local_time(timestamp: i64, timezone_offset: i32) -> DateTime<FixedOffset> {
+    timezone(timezone_offset)
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_else(|| {
+            Utc.timestamp_opt(0, 0)
+                .unwrap()
+                .with_timezone(&timezone(timezone_offset))
+        })
+}
+
+fn calendar_start(year: i32, month: u32, timezone_offset: i32) -> i64 {
+    timezone(timezone_offset)
+        .with_ymd_and_hms(year, month, 1, 0, 0, 0)
+        .single()
+        .map(|date| date.timestamp())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(date: &str) -> i64 {
+        parse_date_time_with_timezone(date, 0).unwrap().timestamp()
+    }
+
+    #[test]
+    fn parse_interval_accepts_names_and_counts() {
+        assert_eq!(parse_interval("hour").unwrap(), Interval::Fixed(3600));
+        assert_eq!(parse_interval(" Week ").unwrap(), Interval::Week);
+        assert_eq!(parse_interval("month").unwrap(), Interval::Month);
+        assert_eq!(parse_interval("15m").unwrap(), Interval::Fixed(900));
+        assert_eq!(parse_interval("6h").unwrap(), Interval::Fixed(21600));
+        assert_eq!(parse_interval("2D").unwrap(), Interval::Fixed(172800));
+    }
+
+    #[test]
+    fn parse_interval_rejects_invalid_values() {
+        for interval in ["", "0h", "-1d", "h", "3w", "fortnight"] {
+            assert!(parse_interval(interval).is_err(), "{}", interval);
+        }
+    }
+
+    #[test]
+    fn fixed_buckets_follow_the_timezone() {
+        let day = Interval::Fixed(86400);
+        let start = bucket_start(utc("2024-01-01 22:00:00"), day, 0);
+        assert_eq!(start, utc("2024-01-01 00:00:00"));
+        assert_eq!(next_bucket(start, day, 0), utc("2024-01-02 00:00:00"));
+
+        // 22:00 UTC is already the next day at UTC+3.
+        let start = bucket_start(utc("2024-01-01 22:00:00"), day, 3);
+        assert_eq!(start, utc("2024-01-01 21:00:00"));
+        assert_eq!(format_with_timezone(start, 3), "2024-01-02 00:00:00");
+    }
+
+    #[test]
+    fn week_buckets_start_on_monday() {
+        // 2024-01-01 is a Monday.
+        let monday = utc("2024-01-01 00:00:00");
+        assert_eq!(
+            bucket_start(utc("2024-01-03 12:00:00"), Interval::Week, 0),
+            monday
+        );
+        assert_eq!(
+            bucket_start(utc("2024-01-07 23:59:59"), Interval::Week, 0),
+            monday
+        );
+        assert_eq!(bucket_start(monday, Interval::Week, 0), monday);
+        assert_eq!(
+            next_bucket(monday, Interval::Week, 0),
+            utc("2024-01-08 00:00:00")
+        );
+
+        // Sunday 23:00 UTC is Monday 01:00 at UTC+2.
+        let start = bucket_start(utc("2024-01-07 23:00:00"), Interval::Week, 2);
+        assert_eq!(start, utc("2024-01-07 22:00:00"));
+        assert_eq!(format_with_timezone(start, 2), "2024-01-08 00:00:00");
+    }
+
+    #[test]
+    fn month_buckets_follow_the_calendar() {
+        let start = bucket_start(utc("2024-02-29 12:00:00"), Interval::Month, 0);
+        assert_eq!(start, utc("2024-02-01 00:00:00"));
+        assert_eq!(
+            next_bucket(start, Interval::Month, 0),
+            utc("2024-03-01 00:00:00")
+        );
+
+        let december = bucket_start(utc("2023-12-31 23:59:59"), Interval::Month, 0);
+        assert_eq!(december, utc("2023-12-01 00:00:00"));
+        assert_eq!(
+            next_bucket(december, Interval::Month, 0),
+            utc("2024-01-01 00:00:00")
+        );
+    }
+
+    #[test]
+    fn month_and_year_buckets_follow_the_timezone() {
+        // 02:00 UTC on March 1st is still February at UTC-5.
+        let start = bucket_start(utc("2024-03-01 02:00:00"), Interval::Month, -5);
+        assert_eq!(start, utc("2024-02-01 05:00:00"));
+        assert_eq!(
+            next_bucket(start, Interval::Month, -5),
+            utc("2024-03-01 05:00:00")
+        );
+
+        let start = bucket_start(utc("2024-01-01 02:00:00"), Interval::Year, -5);
+        assert_eq!(start, utc("2023-01-01 05:00:00"));
+        assert_eq!(
+            next_bucket(start, Interval::Year, -5),
+            utc("2024-01-01 05:00:00")
+        );
+    }
 }
@@ -0,0 +1,146 @@
+use crate::models::validation_error::ValidationError;
+use serde_json::Value;
+
+pub enum ExportFormat {
+    Ndjson,
+    Csv(Vec<String>),
+}
+
+impl ExportFormat {
+    pub fn parse(
+        format: Option<&str>,
+        columns: Option<Vec<String>>,
+    ) -> Result<Self, ValidationError> {
+        match format.map(str::to_lowercase).as_deref() {
+            None | Some("ndjson") => Ok(ExportFormat::Ndjson),
+            Some("csv") => match columns {
+                Some(columns) if !columns.is_empty() => Ok(ExportFormat::Csv(columns)),
+                _ => Err(ValidationError::new("CSV exports require a 'columns' list")),
+            },
+            Some(other) => Err(ValidationError::new(format!(
+                "Export format '{}' must be ndjson or csv",
+                other
+            ))),
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Csv(_) => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Csv(_) => "csv",
+        }
+    }
+
+    pub fn header(&self) -> Option<String> {
+        match self {
+            ExportFormat::Ndjson => None,
+            ExportFormat::Csv(columns) => Some(csv_line(columns.iter().map(String::as_str))),
+        }
+    }
+
+    /// Renders a page of documents, one line per document.
+    pub fn render(&self, documents: &[Value]) -> String {
+        let mut chunk = String::new();
+        for document in documents {
+            match self {
+                ExportFormat::Ndjson => {
+                    chunk.push_str(&document.to_string());
+                    chunk.push('\n');
+                }
+                ExportFormat::Csv(columns) => {
+                    let cells = columns
+                        .iter()
+                        .map(|column| csv_cell(document.get(column)))
+                        .collect::<Vec<String>>();
+                    chunk.push_str(&csv_line(cells.iter().map(String::as_str)));
+                }
+            }
+        }
+        chunk
+    }
+}
+
+fn csv_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn csv_line<'a>(cells: impl Iterator<Item = &'a str>) -> String {
+    let mut line = cells
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn csv(columns: &[&str]) -> ExportFormat {
+        ExportFormat::parse(
+            Some("CSV"),
+            Some(columns.iter().map(|c| c.to_string()).collect()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn csv_cells_are_quoted_when_needed() {
+        let format = csv(&["_key", "title", "tags"]);
+        let documents = [
+            json!({ "_key": "post:1", "title": "plain", "tags": ["a", "b"] }),
+            json!({ "_key": "post:2", "title": "a, \"quoted\"\nline", "tags": null }),
+            json!({ "_key": "post:3", "title": 4.5 }),
+        ];
+        assert_eq!(
+            format.render(&documents),
+            "post:1,plain,\"[\"\"a\"\",\"\"b\"\"]\"\r\n\
+             post:2,\"a, \"\"quoted\"\"\nline\",\r\n\
+             post:3,4.5,\r\n"
+        );
+    }
+
+    #[test]
+    fn csv_header_lists_the_columns() {
+        assert_eq!(
+            csv(&["_key", "a,b"]).header().as_deref(),
+            Some("_key,\"a,b\"\r\n")
+        );
+        assert_eq!(ExportFormat::Ndjson.header(), None);
+    }
+
+    #[test]
+    fn ndjson_renders_one_document_per_line() {
+        let documents = [json!({ "a": 1 }), json!({ "b": "x" })];
+        assert_eq!(
+            ExportFormat::Ndjson.render(&documents),
+            "{\"a\":1}\n{\"b\":\"x\"}\n"
+        );
+    }
+
+    #[test]
+    fn csv_requires_columns() {
+        assert!(ExportFormat::parse(Some("csv"), None).is_err());
+        assert!(ExportFormat::parse(Some("csv"), Some(vec![])).is_err());
+        assert!(ExportFormat::parse(Some("xml"), None).is_err());
+    }
+}
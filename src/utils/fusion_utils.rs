@@ -0,0 +1,215 @@
+use crate::models::search_request::HybridOptions;
+use crate::models::validation_error::ValidationError;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+struct Candidate {
+    document: Value,
+    text: Option<(usize, f64)>,
+    vector: Option<(usize, f64)>,
+}
+
+/// Merges the lexical hits (ranked by `_score`) and the KNN hits (ranked by `_distance`)
+/// into one list ordered by the fused score. Each hit gets the fused `_score` and the
+/// per-leg ranks and scores under `_scores`.
+pub fn fuse_hits(
+    text_hits: Vec<Value>,
+    vector_hits: Vec<Value>,
+    options: &HybridOptions,
+) -> Result<Vec<Value>, ValidationError> {
+    let method = options.method.as_deref().unwrap_or("rrf").to_lowercase();
+    if method != "rrf" && method != "weighted" {
+        return Err(ValidationError::new(
+            "Hybrid method must be rrf or weighted",
+        ));
+    }
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (is_text, hits) in [(true, text_hits), (false, vector_hits)] {
+        for (rank, mut document) in hits.into_iter().enumerate() {
+            let key = document["_key"].as_str().unwrap_or_default().to_string();
+            let score = if is_text {
+                document["_score"].as_f64().unwrap_or(0.0)
+            } else {
+                document["_distance"].as_f64().unwrap_or(0.0)
+            };
+            if let Value::Object(object) = &mut document {
+                object.remove("_score");
+                object.remove("_distance");
+            }
+
+            let position = *positions.entry(key).or_insert_with(|| {
+                candidates.push(Candidate {
+                    document,
+                    text: None,
+                    vector: None,
+                });
+                candidates.len() - 1
+            });
+            if is_text {
+                candidates[position].text = Some((rank + 1, score));
+            } else {
+                candidates[position].vector = Some((rank + 1, score));
+            }
+        }
+    }
+
+    let text_range = score_range(candidates.iter().filter_map(|c| c.text.map(|t| t.1)));
+    let vector_range = score_range(candidates.iter().filter_map(|c| c.vector.map(|v| v.1)));
+    let rrf_k = options.rrf_k.unwrap_or(60.0);
+    let text_weight = options.text_weight.unwrap_or(0.5);
+    let vector_weight = options.vector_weight.unwrap_or(0.5);
+
+    let mut scored = candidates
+        .into_iter()
+        .map(|candidate| {
+            let fused = if method == "rrf" {
+                [candidate.text, candidate.vector]
+                    .iter()
+                    .flatten()
+                    .map(|(rank, _)| 1.0 / (rrf_k + *rank as f64))
+                    .sum::<f64>()
+            } else {
+                // Higher text scores and lower distances are better
+                let text = candidate
+                    .text
+                    .map(|(_, score)| normalize(score, text_range))
+                    .unwrap_or(0.0);
+                let vector = candidate
+                    .vector
+                    .map(|(_, distance)| 1.0 - normalize(distance, vector_range))
+                    .unwrap_or(0.0);
+                text_weight * text + vector_weight * vector
+            };
+            (fused, candidate)
+        })
+        .collect::<Vec<(f64, Candidate)>>();
+
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    Ok(scored
+        .into_iter()
+        .map(|(fused, mut candidate)| {
+            if let Value::Object(object) = &mut candidate.document {
+                object.insert("_score".to_string(), json!(fused));
+                object.insert(
+                    "_scores".to_string(),
+                    json!({
+                        "text_rank": candidate.text.map(|t| t.0),
+                        "text_score": candidate.text.map(|t| t.1),
+                        "vector_rank": candidate.vector.map(|v| v.0),
+                        "vector_distance": candidate.vector.map(|v| v.1)
+                    }),
+                );
+            }
+            candidate.document
+        })
+        .collect())
+}
+
+fn score_range(scores: impl Iterator<Item = f64>) -> (f64, f64) {
+    scores.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), s| {
+        (min.min(s), max.max(s))
+    })
+}
+
+/// Min-max normalization; a leg where every score is equal normalizes to 1.
+fn normalize(score: f64, (min, max): (f64, f64)) -> f64 {
+    if max > min {
+        (score - min) / (max - min)
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_hit(key: &str, score: f64) -> Value {
+        json!({ "_key": key, "_score": score, "title": key })
+    }
+
+    fn vector_hit(key: &str, distance: f64) -> Value {
+        json!({ "_key": key, "_distance": distance, "title": key })
+    }
+
+    fn keys(hits: &[Value]) -> Vec<&str> {
+        hits.iter()
+            .map(|hit| hit["_key"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rrf_sums_reciprocal_ranks() {
+        let fused = fuse_hits(
+            vec![text_hit("a", 3.0), text_hit("b", 2.0)],
+            vec![vector_hit("b", 0.1), vector_hit("c", 0.2)],
+            &HybridOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(keys(&fused), ["b", "a", "c"]);
+        assert_eq!(fused[0]["_score"], json!(1.0 / 62.0 + 1.0 / 61.0));
+        assert_eq!(fused[1]["_score"], json!(1.0 / 61.0));
+        assert_eq!(fused[2]["_score"], json!(1.0 / 62.0));
+    }
+
+    #[test]
+    fn duplicate_keys_are_merged_with_both_ranks() {
+        let fused = fuse_hits(
+            vec![text_hit("a", 3.0), text_hit("b", 2.0)],
+            vec![vector_hit("b", 0.1)],
+            &HybridOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(fused.len(), 2);
+        let b = &fused[0];
+        assert_eq!(b["_key"], "b");
+        assert_eq!(b["title"], "b");
+        assert!(b.get("_distance").is_none());
+        assert_eq!(
+            b["_scores"],
+            json!({
+                "text_rank": 2,
+                "text_score": 2.0,
+                "vector_rank": 1,
+                "vector_distance": 0.1
+            })
+        );
+        assert_eq!(fused[1]["_scores"]["vector_rank"], Value::Null);
+    }
+
+    #[test]
+    fn weighted_fusion_normalizes_each_leg() {
+        let options = HybridOptions {
+            method: Some("Weighted".to_string()),
+            text_weight: Some(0.7),
+            vector_weight: Some(0.3),
+            ..Default::default()
+        };
+        let fused = fuse_hits(
+            vec![text_hit("a", 10.0), text_hit("b", 5.0)],
+            vec![vector_hit("b", 0.1), vector_hit("c", 0.5)],
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(keys(&fused), ["a", "b", "c"]);
+        assert_eq!(fused[0]["_score"], json!(0.7));
+        assert_eq!(fused[1]["_score"], json!(0.3));
+        assert_eq!(fused[2]["_score"], json!(0.0));
+    }
+
+    #[test]
+    fn unknown_method_is_rejected() {
+        let options = HybridOptions {
+            method: Some("max".to_string()),
+            ..Default::default()
+        };
+        assert!(fuse_hits(vec![], vec![], &options).is_err());
+    }
+}
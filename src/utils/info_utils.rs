@@ -0,0 +1,359 @@
+use crate::models::index_info::{AttributeInfo, IndexDefinitionInfo, IndexInfo};
+use crate::utils::redis_utils::{field_pairs, value_to_string, value_to_typed_json};
+use mobc_redis::redis;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Attribute keys followed by a value. Vector parameters are reported in mixed case.
+const ATTRIBUTE_VALUE_KEYS: [&str; 17] = [
+    "identifier",
+    "attribute",
+    "type",
+    "WEIGHT",
+    "SEPARATOR",
+    "PHONETIC",
+    "algorithm",
+    "data_type",
+    "dim",
+    "distance_metric",
+    "M",
+    "ef_construction",
+    "ef_runtime",
+    "epsilon",
+    "initial_cap",
+    "block_size",
+    "coord_system",
+];
+
+/// Parses an FT.INFO reply. Unknown or missing statistics are left at their defaults.
+pub fn parse_index_info(reply: &[redis::Value]) -> IndexInfo {
+    let pairs = field_pairs(reply);
+    let fields = typed_map(&pairs);
+    let number = |name: &str| fields.get(name).and_then(Value::as_f64).unwrap_or_default();
+    let nested = |name: &str| {
+        pairs
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    };
+
+    let mut info = IndexInfo {
+        index_name: nested("index_name")
+            .and_then(|value| value_to_string(value))
+            .unwrap_or_default(),
+        num_docs: number("num_docs") as u64,
+        max_doc_id: number("max_doc_id") as u64,
+        num_terms: number("num_terms") as u64,
+        num_records: number("num_records") as u64,
+        indexing: number("indexing") != 0.0,
+        percent_indexed: number("percent_indexed"),
+        hash_indexing_failures: number("hash_indexing_failures") as u64,
+        number_of_uses: number("number_of_uses") as u64,
+        memory: from_map(fields.clone()),
+        ..IndexInfo::default()
+    };
+
+    if let Some(redis::Value::Bulk(options)) = nested("index_options") {
+        info.index_options = options.iter().filter_map(value_to_string).collect();
+    }
+    if let Some(redis::Value::Bulk(definition)) = nested("index_definition") {
+        info.index_definition = parse_definition(definition);
+    }
+    if let Some(redis::Value::Bulk(attributes)) = nested("attributes") {
+        info.attributes = attributes
+            .iter()
+            .filter_map(|attribute| match attribute {
+                redis::Value::Bulk(items) => Some(parse_attribute(items)),
+                _ => None,
+            })
+            .collect();
+    }
+    if let Some(redis::Value::Bulk(stats)) = nested("gc_stats") {
+        info.gc_stats = from_map(typed_map(&field_pairs(stats)));
+    }
+    if let Some(redis::Value::Bulk(stats)) = nested("cursor_stats") {
+        info.cursor_stats = from_map(typed_map(&field_pairs(stats)));
+    }
+    if let Some(redis::Value::Bulk(errors)) = nested("Index Errors") {
+        info.index_errors = from_map(typed_map(&field_pairs(errors)));
+    }
+
+    info
+}
+
+fn parse_definition(items: &[redis::Value]) -> IndexDefinitionInfo {
+    let mut definition = IndexDefinitionInfo::default();
+    for (key, value) in field_pairs(items) {
+        match (key.as_str(), value) {
+            ("key_type", value) => definition.key_type = value_to_string(value).unwrap_or_default(),
+            ("prefixes", redis::Value::Bulk(prefixes)) => {
+                definition.prefixes = prefixes.iter().filter_map(value_to_string).collect();
+            }
+            ("default_score", value) => {
+                definition.default_score = value_to_typed_json(value).as_f64();
+            }
+            (key, value) => {
+                definition
+                    .options
+                    .insert(key.to_string(), value_to_typed_json(value));
+            }
+        }
+    }
+    definition
+}
+
+/// Attributes are flat lists mixing `key value` pairs and bare flags. Keys not known to carry
+/// a value are flags when upper case, such as SORTABLE or flags added by newer releases.
+fn parse_attribute(items: &[redis::Value]) -> AttributeInfo {
+    let mut attribute = AttributeInfo::default();
+    let mut items = items.iter();
+    while let Some(item) = items.next() {
+        let Some(key) = value_to_string(item) else {
+            continue;
+        };
+        if !ATTRIBUTE_VALUE_KEYS.contains(&key.as_str()) && is_flag(&key) {
+            attribute.flags.push(key);
+            continue;
+        }
+        let Some(value) = items.next() else {
+            break;
+        };
+        match key.as_str() {
+            "identifier" => attribute.identifier = value_to_string(value).unwrap_or_default(),
+            "attribute" => attribute.attribute = value_to_string(value).unwrap_or_default(),
+            "type" => attribute.field_type = value_to_string(value).unwrap_or_default(),
+            "WEIGHT" => attribute.weight = value_to_typed_json(value).as_f64(),
+            "SEPARATOR" => attribute.separator = value_to_string(value),
+            _ => {
+                attribute.options.insert(key, value_to_typed_json(value));
+            }
+        }
+    }
+    attribute
+}
+
+fn is_flag(key: &str) -> bool {
+    key.chars().any(|c| c.is_ascii_uppercase())
+        && key.chars().all(|c| c.is_ascii_uppercase() || c == '_')
+}
+
+fn typed_map(pairs: &[(String, &redis::Value)]) -> Map<String, Value> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.clone(), value_to_typed_json(value)))
+        .collect()
+}
+
+/// Deserializes a stats group; values that are not numbers where one is expected (e.g. `-nan`)
+/// are dropped instead of discarding the whole group.
+fn from_map<T: DeserializeOwned + Default>(map: Map<String, Value>) -> T {
+    serde_json::from_value(Value::Object(map.clone())).unwrap_or_else(|_| {
+        let numbers = map
+            .into_iter()
+            .filter(|(_, value)| value.is_number())
+            .collect::<Map<String, Value>>();
+        serde_json::from_value(Value::Object(numbers)).unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(text: &str) -> redis::Value {
+        redis::Value::Data(text.as_bytes().to_vec())
+    }
+
+    fn bulk(items: &[&str]) -> redis::Value {
+        redis::Value::Bulk(items.iter().map(|item| data(item)).collect())
+    }
+
+    /// FT.INFO of a JSON index with TEXT, TAG and VECTOR attributes, as sent by RediSearch.
+    fn captured_reply() -> Vec<redis::Value> {
+        vec![
+            data("index_name"),
+            data("posts_v2"),
+            data("index_options"),
+            redis::Value::Bulk(vec![]),
+            data("index_definition"),
+            redis::Value::Bulk(vec![
+                data("key_type"),
+                data("JSON"),
+                data("prefixes"),
+                bulk(&["post:"]),
+                data("default_score"),
+                data("1"),
+                data("language_field"),
+                data("__language"),
+            ]),
+            data("attributes"),
+            redis::Value::Bulk(vec![
+                bulk(&[
+                    "identifier",
+                    "$.title",
+                    "attribute",
+                    "title",
+                    "type",
+                    "TEXT",
+                    "WEIGHT",
+                    "2",
+                    "SORTABLE",
+                    "NOSTEM",
+                ]),
+                bulk(&[
+                    "identifier",
+                    "$.channel",
+                    "attribute",
+                    "channel",
+                    "type",
+                    "TAG",
+                    "SEPARATOR",
+                    ",",
+                    "CASESENSITIVE",
+                    "INDEXALL",
+                    "SORTABLE",
+                    "UNF",
+                ]),
+                bulk(&[
+                    "identifier",
+                    "$.embedding",
+                    "attribute",
+                    "embedding",
+                    "type",
+                    "VECTOR",
+                    "algorithm",
+                    "HNSW",
+                    "data_type",
+                    "FLOAT32",
+                    "dim",
+                    "4",
+                    "distance_metric",
+                    "COSINE",
+                    "M",
+                    "16",
+                    "ef_construction",
+                    "200",
+                ]),
+            ]),
+            data("num_docs"),
+            data("3"),
+            data("max_doc_id"),
+            data("4"),
+            data("num_terms"),
+            data("12"),
+            data("num_records"),
+            data("30"),
+            data("inverted_sz_mb"),
+            data("1.1444091796875e-4"),
+            data("vector_index_sz_mb"),
+            data("0.0412"),
+            data("indexing"),
+            data("0"),
+            data("percent_indexed"),
+            data("1"),
+            data("hash_indexing_failures"),
+            data("1"),
+            data("number_of_uses"),
+            redis::Value::Int(7),
+            data("gc_stats"),
+            redis::Value::Bulk(vec![
+                data("bytes_collected"),
+                data("0"),
+                data("total_cycles"),
+                data("0"),
+                data("average_cycle_time_ms"),
+                data("-nan"),
+            ]),
+            data("cursor_stats"),
+            redis::Value::Bulk(vec![
+                data("global_idle"),
+                redis::Value::Int(0),
+                data("index_capacity"),
+                redis::Value::Int(128),
+            ]),
+            data("Index Errors"),
+            bulk(&[
+                "indexing failures",
+                "1",
+                "last indexing error",
+                "Invalid JSON value at $.ts",
+                "last indexing error key",
+                "post:4",
+            ]),
+        ]
+    }
+
+    #[test]
+    fn parses_definition_and_statistics() {
+        let info = parse_index_info(&captured_reply());
+
+        assert_eq!(info.index_name, "posts_v2");
+        assert!(info.index_options.is_empty());
+        assert_eq!(info.index_definition.key_type, "JSON");
+        assert_eq!(info.index_definition.prefixes, ["post:"]);
+        assert_eq!(info.index_definition.default_score, Some(1.0));
+        assert_eq!(
+            info.index_definition.options.get("language_field"),
+            Some(&Value::from("__language"))
+        );
+
+        assert_eq!(info.num_docs, 3);
+        assert_eq!(info.max_doc_id, 4);
+        assert_eq!(info.num_records, 30);
+        assert!(!info.indexing);
+        assert_eq!(info.percent_indexed, 1.0);
+        assert_eq!(info.number_of_uses, 7);
+        assert_eq!(info.memory.inverted_sz_mb, Some(1.1444091796875e-4));
+        assert_eq!(info.memory.vector_index_sz_mb, Some(0.0412));
+        assert_eq!(info.memory.doc_table_size_mb, None);
+    }
+
+    #[test]
+    fn keeps_numeric_stats_next_to_nan() {
+        let info = parse_index_info(&captured_reply());
+
+        assert_eq!(info.gc_stats.bytes_collected, Some(0.0));
+        assert_eq!(info.gc_stats.total_cycles, Some(0.0));
+        assert_eq!(info.gc_stats.average_cycle_time_ms, None);
+        assert_eq!(info.cursor_stats.index_capacity, Some(128.0));
+        assert_eq!(info.index_errors.indexing_failures, Some(1.0));
+        assert_eq!(
+            info.index_errors.last_indexing_error_key.as_deref(),
+            Some("post:4")
+        );
+    }
+
+    #[test]
+    fn parses_attribute_flags_and_options() {
+        let info = parse_index_info(&captured_reply());
+        let [title, channel, embedding] = info.attributes.as_slice() else {
+            panic!("expected three attributes, got {:?}", info.attributes);
+        };
+
+        assert_eq!(title.identifier, "$.title");
+        assert_eq!(title.attribute, "title");
+        assert_eq!(title.field_type, "TEXT");
+        assert_eq!(title.weight, Some(2.0));
+        assert_eq!(title.flags, ["SORTABLE", "NOSTEM"]);
+
+        assert_eq!(channel.separator.as_deref(), Some(","));
+        assert_eq!(
+            channel.flags,
+            ["CASESENSITIVE", "INDEXALL", "SORTABLE", "UNF"]
+        );
+        assert!(channel.options.is_empty());
+
+        assert_eq!(embedding.field_type, "VECTOR");
+        assert!(embedding.flags.is_empty());
+        assert_eq!(
+            embedding.options.get("algorithm"),
+            Some(&Value::from("HNSW"))
+        );
+        assert_eq!(embedding.options.get("dim"), Some(&Value::from(4)));
+        assert_eq!(embedding.options.get("M"), Some(&Value::from(16)));
+        assert_eq!(
+            embedding.options.get("distance_metric"),
+            Some(&Value::from("COSINE"))
+        );
+    }
+}
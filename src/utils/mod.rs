@@ -1 +1,9 @@
 pub mod date_utils;
+pub mod export_utils;
+pub mod fusion_utils;
+pub mod info_utils;
+pub mod query_utils;
+pub mod redis_utils;
+pub mod schema_utils;
+pub mod suggest_utils;
+pub mod validation_utils;
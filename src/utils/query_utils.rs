@@ -0,0 +1,842 @@
+use crate::models::aggregate_request::{Reducer, SortKey};
+use crate::models::filter::{
+    Filter, GeoPolygonFilter, GeoRadiusFilter, RangeFilter, TagFilter, TermFilter,
+};
+use crate::models::search_request::{
+    GeoSort, HighlightOptions, KnnQuery, ReturnField, SpellcheckOptions, SummarizeOptions,
+};
+use crate::models::validation_error::ValidationError;
+use crate::utils::date_utils::Interval;
+use crate::utils::schema_utils::vector_data_type;
+
+const GEO_UNITS: [&str; 4] = ["m", "km", "mi", "ft"];
+const GEO_RELATIONS: [&str; 4] = ["WITHIN", "CONTAINS", "INTERSECTS", "DISJOINT"];
+const SCORERS: [&str; 7] = [
+    "TFIDF",
+    "TFIDF.DOCNORM",
+    "BM25",
+    "BM25STD",
+    "DISMAX",
+    "DOCSCORE",
+    "HAMMING",
+];
+
+/// Builds the FT.SEARCH query from the free-text `q`, the structured filter and the flat
+/// numeric/tag filter lists. Numeric ranges are expressed in the query rather than as
+/// FILTER arguments so the same query works with FT.AGGREGATE.
+pub fn build_query(
+    q: Option<&str>,
+    filter: Option<&Filter>,
+    numeric_filters: &[RangeFilter],
+    tag_filters: &[TagFilter],
+) -> Result<String, ValidationError> {
+    let mut clauses = Vec::new();
+
+    if let Some(text) = q.map(str::trim).filter(|t| !t.is_empty() && *t != "*") {
+        clauses.push(format!("({})", escape_text(text)));
+    }
+
+    if let Some(filter) = filter {
+        clauses.push(compile_filter(filter)?);
+    }
+
+    for range in numeric_filters {
+        clauses.push(compile_range(range)?);
+    }
+
+    for tag in tag_filters {
+        clauses.push(compile_tag(tag)?);
+    }
+
+    if clauses.is_empty() {
+        Ok("*".to_string())
+    } else {
+        Ok(clauses.join(" "))
+    }
+}
+
+pub fn compile_filter(filter: &Filter) -> Result<String, ValidationError> {
+    match filter {
+        Filter::And(filters) => compile_group(filters, " ", "and"),
+        Filter::Or(filters) => compile_group(filters, " | ", "or"),
+        Filter::Not(inner) => Ok(format!("-({})", compile_filter(inner)?)),
+        Filter::Term(term) => compile_term(term),
+        Filter::Phrase(term) => compile_phrase(term),
+        Filter::Prefix(term) => compile_prefix(term),
+        Filter::Range(range) => compile_range(range),
+        Filter::TagIn(tag) => compile_tag(tag),
+        Filter::GeoRadius(geo) => compile_geo_radius(geo),
+    }
+}
+
+fn compile_group(
+    filters: &[Filter],
+    separator: &str,
+    name: &str,
+) -> Result<String, ValidationError> {
+    if filters.is_empty() {
+        return Err(ValidationError::new(format!(
+            "'{}' filter requires at least one clause",
+            name
+        )));
+    }
+
+    let clauses = filters
+        .iter()
+        .map(compile_filter)
+        .collect::<Result<Vec<String>, ValidationError>>()?;
+
+    Ok(format!("({})", clauses.join(separator)))
+}
+
+fn compile_term(term: &TermFilter) -> Result<String, ValidationError> {
+    let value = term.value.trim();
+    if value.is_empty() {
+        return Err(ValidationError::new("'term' filter value is empty"));
+    }
+
+    Ok(format!(
+        "{}({})",
+        field_prefix(term.field.as_deref())?,
+        escape_text(value)
+    ))
+}
+
+fn compile_phrase(term: &TermFilter) -> Result<String, ValidationError> {
+    let value = term.value.trim();
+    if value.is_empty() {
+        return Err(ValidationError::new("'phrase' filter value is empty"));
+    }
+
+    Ok(format!(
+        "{}\"{}\"",
+        field_prefix(term.field.as_deref())?,
+        escape_text(value)
+    ))
+}
+
+fn compile_prefix(term: &TermFilter) -> Result<String, ValidationError> {
+    let value = term.value.trim();
+    if value.chars().count() < 2 || value.chars().any(char::is_whitespace) {
+        return Err(ValidationError::new(
+            "'prefix' filter value must be a single word of at least 2 characters",
+        ));
+    }
+
+    Ok(format!(
+        "{}{}*",
+        field_prefix(term.field.as_deref())?,
+        escape_text(value)
+    ))
+}
+
+pub fn compile_range(range: &RangeFilter) -> Result<String, ValidationError> {
+    validate_field_name(&range.field)?;
+
+    if range.gt.is_some() && range.gte.is_some() {
+        return Err(ValidationError::new(format!(
+            "Range on '{}' cannot set both 'gt' and 'gte'",
+            range.field
+        )));
+    }
+    if range.lt.is_some() && range.lte.is_some() {
+        return Err(ValidationError::new(format!(
+            "Range on '{}' cannot set both 'lt' and 'lte'",
+            range.field
+        )));
+    }
+
+    let lower = match (range.gt, range.gte) {
+        (Some(v), _) => format!("({}", v),
+        (_, Some(v)) => v.to_string(),
+        _ => "-inf".to_string(),
+    };
+    let upper = match (range.lt, range.lte) {
+        (Some(v), _) => format!("({}", v),
+        (_, Some(v)) => v.to_string(),
+        _ => "+inf".to_string(),
+    };
+
+    if let (Some(min), Some(max)) = (range.gt.or(range.gte), range.lt.or(range.lte)) {
+        if min > max {
+            return Err(ValidationError::new(format!(
+                "Range on '{}' has a lower bound greater than its upper bound",
+                range.field
+            )));
+        }
+    }
+
+    Ok(format!("@{}:[{} {}]", range.field, lower, upper))
+}
+
+pub fn compile_tag(tag: &TagFilter) -> Result<String, ValidationError> {
+    validate_field_name(&tag.field)?;
+
+    if tag.values.is_empty() || tag.values.iter().any(|v| v.trim().is_empty()) {
+        return Err(ValidationError::new(format!(
+            "Tag filter on '{}' requires non-empty values",
+            tag.field
+        )));
+    }
+
+    let values = tag
+        .values
+        .iter()
+        .map(|v| escape_tag(v.trim()))
+        .collect::<Vec<String>>();
+
+    Ok(format!("@{}:{{{}}}", tag.field, values.join(" | ")))
+}
+
+pub fn compile_geo_radius(geo: &GeoRadiusFilter) -> Result<String, ValidationError> {
+    validate_field_name(&geo.field)?;
+    validate_coordinates(&geo.field, geo.lon, geo.lat)?;
+    if geo.radius <= 0.0 {
+        return Err(ValidationError::new(format!(
+            "Geo filter on '{}' requires a positive radius",
+            geo.field
+        )));
+    }
+    let unit = geo_unit(geo.unit.as_deref())?;
+
+    Ok(format!(
+        "@{}:[{} {} {} {}]",
+        geo.field, geo.lon, geo.lat, geo.radius, unit
+    ))
+}
+
+/// Compiles a polygon filter on a GEOSHAPE field. The shape is bound as the `$<param>`
+/// query parameter, so this returns the clause and the WKT value to bind.
+pub fn compile_geo_polygon(
+    polygon: &GeoPolygonFilter,
+    param: &str,
+) -> Result<(String, String), ValidationError> {
+    validate_field_name(&polygon.field)?;
+
+    let relation = polygon
+        .relation
+        .as_deref()
+        .unwrap_or("WITHIN")
+        .to_uppercase();
+    if !GEO_RELATIONS.contains(&relation.as_str()) {
+        return Err(ValidationError::new(format!(
+            "Polygon relation must be one of {}",
+            GEO_RELATIONS.join(", ")
+        )));
+    }
+
+    let mut points = polygon.points.clone();
+    if points.iter().flatten().any(|c| !c.is_finite()) {
+        return Err(ValidationError::new(format!(
+            "Polygon filter on '{}' has invalid coordinates",
+            polygon.field
+        )));
+    }
+    if points.first() != points.last() {
+        points.extend(points.first().copied());
+    }
+    // A closed ring needs at least three distinct vertices plus the closing one
+    if points.len() < 4 {
+        return Err(ValidationError::new(format!(
+            "Polygon filter on '{}' requires at least 3 points",
+            polygon.field
+        )));
+    }
+
+    let ring = points
+        .iter()
+        .map(|[x, y]| format!("{} {}", x, y))
+        .collect::<Vec<String>>();
+    Ok((
+        format!("@{}:[{} ${}]", polygon.field, relation, param),
+        format!("POLYGON(({}))", ring.join(", ")),
+    ))
+}
+
+/// APPLY expression computing the distance from the `geo_sort` point in its unit;
+/// `geodistance` itself returns meters.
+pub fn geo_distance_expression(sort: &GeoSort) -> Result<String, ValidationError> {
+    validate_field_name(&sort.field)?;
+    validate_coordinates(&sort.field, sort.lon, sort.lat)?;
+
+    let meters = match geo_unit(sort.unit.as_deref())?.as_str() {
+        "m" => 1.0,
+        "mi" => 1609.344,
+        "ft" => 0.3048,
+        _ => 1000.0,
+    };
+    Ok(format!(
+        "geodistance(@{}, {}, {}) / {}",
+        sort.field, sort.lon, sort.lat, meters
+    ))
+}
+
+fn validate_coordinates(field: &str, lon: f64, lat: f64) -> Result<(), ValidationError> {
+    if !(-180.0..=180.0).contains(&lon) || !(-85.05112878..=85.05112878).contains(&lat) {
+        return Err(ValidationError::new(format!(
+            "Geo filter on '{}' has invalid coordinates",
+            field
+        )));
+    }
+    Ok(())
+}
+
+fn geo_unit(unit: Option<&str>) -> Result<String, ValidationError> {
+    let unit = unit.unwrap_or("km").to_lowercase();
+    if !GEO_UNITS.contains(&unit.as_str()) {
+        return Err(ValidationError::new(format!(
+            "Geo unit must be one of {}",
+            GEO_UNITS.join(", ")
+        )));
+    }
+    Ok(unit)
+}
+
+fn field_prefix(field: Option<&str>) -> Result<String, ValidationError> {
+    match field {
+        Some(field) => {
+            validate_field_name(field)?;
+            Ok(format!("@{}:", field))
+        }
+        None => Ok(String::new()),
+    }
+}
+
+/// Field names are interpolated into the query, so only plain attribute names are accepted.
+pub fn validate_field_name(field: &str) -> Result<(), ValidationError> {
+    let mut chars = field.chars();
+    let valid = match chars.next() {
+        Some(first) => {
+            (first.is_ascii_alphabetic() || first == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new(format!(
+            "Invalid field name '{}'",
+            field
+        )))
+    }
+}
+
+/// Escapes query operators in free text, keeping whitespace as the term separator.
+pub fn escape_text(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| escape_chars(word, false))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Escapes a TAG value, including spaces which would otherwise split the tag.
+pub fn escape_tag(value: &str) -> String {
+    escape_chars(value, true)
+}
+
+fn escape_chars(value: &str, escape_whitespace: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_punctuation() || (escape_whitespace && c.is_whitespace()) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+const REDUCER_FUNCTIONS: [&str; 7] = [
+    "COUNT",
+    "SUM",
+    "AVG",
+    "MIN",
+    "MAX",
+    "COUNT_DISTINCT",
+    "TOLIST",
+];
+
+/// Compiles a reducer into its `REDUCE` arguments and returns them with the output alias.
+pub fn compile_reducer(reducer: &Reducer) -> Result<(Vec<String>, String), ValidationError> {
+    let function = reducer.function.to_uppercase();
+    if !REDUCER_FUNCTIONS.contains(&function.as_str()) {
+        return Err(ValidationError::new(format!(
+            "Reducer must be one of {}",
+            REDUCER_FUNCTIONS.join(", ")
+        )));
+    }
+
+    let mut args = vec!["REDUCE".to_string(), function.clone()];
+    let default_alias = match (&reducer.field, function.as_str()) {
+        (None, "COUNT") => {
+            args.push("0".to_string());
+            "count".to_string()
+        }
+        (Some(field), _) => {
+            validate_field_name(field)?;
+            args.push("1".to_string());
+            args.push(format!("@{}", field));
+            format!("{}_{}", function.to_lowercase(), field)
+        }
+        (None, _) => {
+            return Err(ValidationError::new(format!(
+                "Reducer {} requires a field",
+                function
+            )))
+        }
+    };
+
+    let alias = reducer.alias.clone().unwrap_or(default_alias);
+    validate_field_name(&alias)?;
+    args.push("AS".to_string());
+    args.push(alias.clone());
+
+    Ok((args, alias))
+}
+
+/// Attributes referenced as `@name` in an APPLY or FILTER expression, outside string literals.
+pub fn expression_fields(expression: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut quote = None;
+    let mut chars = expression.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '@') => {
+                let start = i + 1;
+                let mut end = start;
+                while let Some((j, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || *c == '_') {
+                        break;
+                    }
+                    end = j + c.len_utf8();
+                    chars.next();
+                }
+                if end > start {
+                    fields.push(&expression[start..end]);
+                }
+            }
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// Compiles sort keys into `SORTBY` arguments, validating each order as ASC or DESC.
+pub fn compile_sort_keys(keys: &[SortKey]) -> Result<Vec<String>, ValidationError> {
+    let mut args = vec!["SORTBY".to_string(), (keys.len() * 2).to_string()];
+    for key in keys {
+        validate_field_name(&key.field)?;
+        args.push(format!("@{}", key.field));
+        args.push(sort_order(key.order.as_deref())?.to_string());
+    }
+    Ok(args)
+}
+
+pub fn sort_order(order: Option<&str>) -> Result<&'static str, ValidationError> {
+    match order.map(str::to_uppercase).as_deref() {
+        None | Some("ASC") => Ok("ASC"),
+        Some("DESC") => Ok("DESC"),
+        Some(other) => Err(ValidationError::new(format!(
+            "Sort order '{}' must be ASC or DESC",
+            other
+        ))),
+    }
+}
+
+/// APPLY expression mapping a timestamp field to the start of its histogram bucket.
+/// Month and year buckets both use `month()`; years are folded together by the caller.
+pub fn histogram_expression(field: &str, interval: Interval, timezone_offset: i32) -> String {
+    let offset = timezone_offset as i64 * 3600;
+    match interval {
+        Interval::Fixed(width) => format!(
+            "floor((@{} + {}) / {}) * {} - {}",
+            field, offset, width, width, offset
+        ),
+        Interval::Week => format!(
+            "floor((@{} + {} - 345600) / 604800) * 604800 + 345600 - {}",
+            field, offset, offset
+        ),
+        Interval::Month | Interval::Year => format!("month(@{} + {}) - {}", field, offset, offset),
+    }
+}
+
+pub struct SnippetArgs {
+    /// Fields that must be returned for the snippets to appear in the response.
+    pub fields: Vec<String>,
+    pub args: Vec<String>,
+}
+
+/// Compiles the HIGHLIGHT and SUMMARIZE options of a search.
+pub fn compile_snippet_args(
+    highlight: Option<&HighlightOptions>,
+    summarize: Option<&SummarizeOptions>,
+) -> Result<SnippetArgs, ValidationError> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut args = Vec::new();
+
+    if let Some(summarize) = summarize {
+        let summarize_fields = summarize
+            .fields
+            .clone()
+            .unwrap_or_else(|| vec!["post_message".to_string()]);
+        args.push("SUMMARIZE".to_string());
+        push_snippet_fields(&summarize_fields, &mut fields, &mut args)?;
+        if let Some(frags) = summarize.frags {
+            args.extend(["FRAGS".to_string(), frags.to_string()]);
+        }
+        if let Some(len) = summarize.len {
+            args.extend(["LEN".to_string(), len.to_string()]);
+        }
+        if let Some(separator) = &summarize.separator {
+            args.extend(["SEPARATOR".to_string(), separator.clone()]);
+        }
+    }
+
+    if let Some(highlight) = highlight {
+        let highlight_fields = highlight
+            .fields
+            .clone()
+            .unwrap_or_else(|| vec!["post_title".to_string(), "post_message".to_string()]);
+        args.push("HIGHLIGHT".to_string());
+        push_snippet_fields(&highlight_fields, &mut fields, &mut args)?;
+        match (&highlight.open_tag, &highlight.close_tag) {
+            (Some(open), Some(close)) => {
+                args.extend(["TAGS".to_string(), open.clone(), close.clone()]);
+            }
+            (None, None) => {}
+            _ => {
+                return Err(ValidationError::new(
+                    "Highlight 'open_tag' and 'close_tag' must be given together",
+                ))
+            }
+        }
+    }
+
+    Ok(SnippetArgs { fields, args })
+}
+
+fn push_snippet_fields(
+    snippet_fields: &[String],
+    fields: &mut Vec<String>,
+    args: &mut Vec<String>,
+) -> Result<(), ValidationError> {
+    if snippet_fields.is_empty() {
+        return Err(ValidationError::new(
+            "Highlight and summarize fields must not be empty",
+        ));
+    }
+
+    args.push("FIELDS".to_string());
+    args.push(snippet_fields.len().to_string());
+    for field in snippet_fields {
+        validate_field_name(field)?;
+        args.push(field.clone());
+        if !fields.contains(field) {
+            fields.push(field.clone());
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_scorer(scorer: &str) -> Result<String, ValidationError> {
+    let scorer = scorer.to_uppercase();
+    if SCORERS.contains(&scorer.as_str()) {
+        Ok(scorer)
+    } else {
+        Err(ValidationError::new(format!(
+            "Scorer must be one of {}",
+            SCORERS.join(", ")
+        )))
+    }
+}
+
+/// Compiles projected fields into `RETURN` arguments (without the leading count).
+pub fn compile_return_fields(fields: &[ReturnField]) -> Result<Vec<String>, ValidationError> {
+    let mut args = Vec::new();
+    for field in fields {
+        if field.path.is_empty() || field.path.chars().any(char::is_whitespace) {
+            return Err(ValidationError::new(format!(
+                "Invalid return path '{}'",
+                field.path
+            )));
+        }
+        args.push(field.path.clone());
+        if let Some(alias) = &field.alias {
+            validate_field_name(alias)?;
+            args.push("AS".to_string());
+            args.push(alias.clone());
+        }
+    }
+    Ok(args)
+}
+
+/// Encodes the index and FT.AGGREGATE cursor id into an opaque continuation token.
+pub fn encode_cursor(index: &str, cursor_id: i64) -> String {
+    format!("{}:{}", index, cursor_id)
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn decode_cursor(token: &str) -> Result<(String, i64), ValidationError> {
+    let invalid = || ValidationError::new("Invalid cursor");
+
+    if !token.len().is_multiple_of(2) || !token.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..token.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&token[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    let decoded = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+    let (index, cursor_id) = decoded.rsplit_once(':').ok_or_else(invalid)?;
+    let cursor_id = cursor_id.parse::<i64>().map_err(|_| invalid())?;
+    Ok((index.to_string(), cursor_id))
+}
+
+/// Wraps a prefilter query into a KNN query; the vector is bound as the `$BLOB` parameter.
+pub fn compile_knn_query(
+    prefilter: &str,
+    knn: &KnnQuery,
+    default_k: i64,
+) -> Result<String, ValidationError> {
+    validate_field_name(&knn.field)?;
+
+    let k = knn.k.unwrap_or(default_k);
+    if k <= 0 {
+        return Err(ValidationError::new("KNN 'k' must be positive"));
+    }
+
+    let prefilter = if prefilter == "*" {
+        "*".to_string()
+    } else {
+        format!("({})", prefilter)
+    };
+    let ef_runtime = knn
+        .ef_runtime
+        .map(|ef| format!(" EF_RUNTIME {}", ef))
+        .unwrap_or_default();
+
+    Ok(format!(
+        "{}=>[KNN {} @{} $BLOB{} AS __distance]",
+        prefilter, k, knn.field, ef_runtime
+    ))
+}
+
+/// Serializes a query vector into the little-endian blob RediSearch expects.
+pub fn vector_blob(vector: &[f64], data_type: Option<&str>) -> Result<Vec<u8>, ValidationError> {
+    if vector.is_empty() {
+        return Err(ValidationError::new("KNN 'vector' must not be empty"));
+    }
+
+    Ok(match vector_data_type(data_type)? {
+        "FLOAT64" => vector.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        _ => vector
+            .iter()
+            .flat_map(|v| (*v as f32).to_le_bytes())
+            .collect(),
+    })
+}
+
+/// FT.SPELLCHECK arguments following the index and the query.
+/// Name of the FT.DICTADD dictionary `name` declared on index `index`, so indexes declaring
+/// the same dictionary name don't share terms.
+pub fn custom_dictionary(index: &str, name: &str) -> String {
+    format!("{}:{}", index, name)
+}
+
+/// FT.SPELLCHECK arguments; `include` and `exclude` name dictionaries of `index`.
+pub fn compile_spellcheck_args(
+    index: &str,
+    options: &SpellcheckOptions,
+) -> Result<Vec<String>, ValidationError> {
+    let mut args = Vec::new();
+
+    if let Some(distance) = options.distance {
+        if !(1..=4).contains(&distance) {
+            return Err(ValidationError::new(
+                "Spellcheck distance must be between 1 and 4",
+            ));
+        }
+        args.push("DISTANCE".to_string());
+        args.push(distance.to_string());
+    }
+
+    let dictionaries = [
+        ("INCLUDE", options.include.as_deref().unwrap_or_default()),
+        ("EXCLUDE", options.exclude.as_deref().unwrap_or_default()),
+    ];
+    for (mode, names) in dictionaries {
+        for name in names {
+            if name.trim().is_empty() {
+                return Err(ValidationError::new("Dictionary names must not be empty"));
+            }
+            args.extend([
+                "TERMS".to_string(),
+                mode.to_string(),
+                custom_dictionary(index, name),
+            ]);
+        }
+    }
+
+    Ok(args)
+}
+
+/// Replaces the misspelled words of `q` with their corrections. FT.SPELLCHECK reports
+/// lowercased terms, so words are compared case-insensitively.
+pub fn rewrite_query(q: &str, corrections: &[(String, String)]) -> String {
+    let mut rewritten = String::with_capacity(q.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, rewritten: &mut String| {
+        let lower = word.to_lowercase();
+        match corrections.iter().find(|(term, _)| *term == lower) {
+            Some((_, correction)) => rewritten.push_str(correction),
+            None => rewritten.push_str(word),
+        }
+        word.clear();
+    };
+
+    for c in q.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut rewritten);
+            rewritten.push(c);
+        }
+    }
+    flush(&mut word, &mut rewritten);
+
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(field: Option<&str>, value: &str) -> TermFilter {
+        TermFilter {
+            field: field.map(str::to_string),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn escape_text_escapes_query_operators() {
+        assert_eq!(escape_text("a-b"), "a\\-b");
+        assert_eq!(
+            escape_text("@title:(x|y) -z ~w"),
+            "\\@title\\:\\(x\\|y\\) \\-z \\~w"
+        );
+        assert_eq!(
+            escape_text("\"quoted\" {tag} [1 2]"),
+            "\\\"quoted\\\" \\{tag\\} \\[1 2\\]"
+        );
+        assert_eq!(escape_text("100% $param *"), "100\\% \\$param \\*");
+    }
+
+    #[test]
+    fn escape_text_keeps_words_separated() {
+        assert_eq!(escape_text("  lebron   james "), "lebron james");
+        assert_eq!(escape_text("勒布朗 詹姆斯"), "勒布朗 詹姆斯");
+    }
+
+    #[test]
+    fn escape_tag_escapes_whitespace_and_punctuation() {
+        assert_eq!(escape_tag("New York"), "New\\ York");
+        assert_eq!(escape_tag("a|b}"), "a\\|b\\}");
+        assert_eq!(escape_tag("c++ & c#"), "c\\+\\+\\ \\&\\ c\\#");
+    }
+
+    #[test]
+    fn compile_filter_escapes_values() {
+        let filter = Filter::And(vec![
+            Filter::Term(term(Some("post_title"), "foo) | @other:(bar")),
+            Filter::Phrase(term(None, "say \"hi\"")),
+            Filter::TagIn(TagFilter {
+                field: "channel".to_string(),
+                values: vec!["NBA} | {x".to_string()],
+            }),
+        ]);
+
+        assert_eq!(
+            compile_filter(&filter).unwrap(),
+            "(@post_title:(foo\\) \\| \\@other\\:\\(bar) \"say \\\"hi\\\"\" \
+             @channel:{NBA\\}\\ \\|\\ \\{x})"
+        );
+    }
+
+    #[test]
+    fn compile_filter_rejects_injected_field_names() {
+        assert!(compile_filter(&Filter::Term(term(Some("title:(x) | @a"), "y"))).is_err());
+        assert!(compile_tag(&TagFilter {
+            field: "tags}".to_string(),
+            values: vec!["x".to_string()],
+        })
+        .is_err());
+        assert!(validate_field_name("post_title").is_ok());
+        assert!(validate_field_name("1st").is_err());
+    }
+
+    #[test]
+    fn build_query_wraps_free_text() {
+        assert_eq!(build_query(None, None, &[], &[]).unwrap(), "*");
+        assert_eq!(build_query(Some(" * "), None, &[], &[]).unwrap(), "*");
+        assert_eq!(
+            build_query(Some("a -b"), None, &[], &[]).unwrap(),
+            "(a \\-b)"
+        );
+    }
+
+    #[test]
+    fn cursor_tokens_round_trip() {
+        let token = encode_cursor("my:index_v2", 42);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(
+            decode_cursor(&token).unwrap(),
+            ("my:index_v2".to_string(), 42)
+        );
+    }
+
+    #[test]
+    fn invalid_cursor_tokens_are_rejected() {
+        let no_id = "myIndex:"
+            .bytes()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        for token in ["", "abc", "zz", "6d79496e646578", &no_id, "é0"] {
+            assert!(decode_cursor(token).is_err(), "{}", token);
+        }
+    }
+
+    #[test]
+    fn spellcheck_dictionaries_are_scoped_to_the_index() {
+        let options = SpellcheckOptions {
+            distance: Some(2),
+            include: Some(vec!["players".to_string()]),
+            exclude: Some(vec!["slang".to_string()]),
+            always: false,
+        };
+        assert_eq!(
+            compile_spellcheck_args("myIndex", &options).unwrap(),
+            [
+                "DISTANCE",
+                "2",
+                "TERMS",
+                "INCLUDE",
+                "myIndex:players",
+                "TERMS",
+                "EXCLUDE",
+                "myIndex:slang"
+            ]
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use mobc_redis::redis;
+use serde_json::{json, Value};
+
+/// Reads a bulk string, integer or status reply as text.
+pub fn value_to_string(value: &redis::Value) -> Option<String> {
+    match value {
+        redis::Value::Data(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        redis::Value::Int(i) => Some(i.to_string()),
+        redis::Value::Status(s) => Some(s.clone()),
+        redis::Value::Okay => Some("OK".to_string()),
+        _ => None,
+    }
+}
+
+/// Converts a reply to JSON, keeping nested arrays and turning bulk strings into strings.
+pub fn value_to_json(value: &redis::Value) -> Value {
+    match value {
+        redis::Value::Nil => Value::Null,
+        redis::Value::Int(i) => json!(i),
+        redis::Value::Bulk(items) => Value::Array(items.iter().map(value_to_json).collect()),
+        other => value_to_string(other)
+            .map(Value::String)
+            .unwrap_or(Value::Null),
+    }
+}
+
+/// Like `value_to_json`, but turns numeric strings into JSON numbers.
+pub fn value_to_typed_json(value: &redis::Value) -> Value {
+    match value {
+        redis::Value::Bulk(items) => Value::Array(items.iter().map(value_to_typed_json).collect()),
+        redis::Value::Data(_) | redis::Value::Status(_) => {
+            let text = value_to_string(value).unwrap_or_default();
+            parse_number(&text).unwrap_or(Value::String(text))
+        }
+        other => value_to_json(other),
+    }
+}
+
+pub fn parse_number(text: &str) -> Option<Value> {
+    if let Ok(i) = text.parse::<i64>() {
+        return Some(json!(i));
+    }
+    text.parse::<f64>()
+        .ok()
+        .filter(|f| f.is_finite())
+        .map(|f| json!(f))
+}
+
+/// Splits a flat `[name, value, name, value, ...]` reply into pairs.
+pub fn field_pairs(items: &[redis::Value]) -> Vec<(String, &redis::Value)> {
+    items
+        .chunks(2)
+        .filter_map(|pair| match pair {
+            [name, value] => value_to_string(name).map(|name| (name, value)),
+            _ => None,
+        })
+        .collect()
+}
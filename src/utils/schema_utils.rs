@@ -0,0 +1,754 @@
+use crate::models::index_info::{AttributeInfo, IndexInfo};
+use crate::models::index_request::{IndexRequest, SchemaField, VectorOptions};
+use crate::models::validation_error::ValidationError;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const VECTOR_ALGORITHMS: [&str; 2] = ["FLAT", "HNSW"];
+const DISTANCE_METRICS: [&str; 3] = ["L2", "IP", "COSINE"];
+const VECTOR_DATA_TYPES: [&str; 2] = ["FLOAT32", "FLOAT64"];
+const COORD_SYSTEMS: [&str; 2] = ["SPHERICAL", "FLAT"];
+const PHONETIC_MATCHERS: [&str; 4] = ["dm:en", "dm:fr", "dm:pt", "dm:es"];
+
+/// Compiles an index definition into FT.CREATE arguments (without the command name).
+pub fn compile_create_args(request: &IndexRequest) -> Result<Vec<String>, ValidationError> {
+    let mut args = vec![request.index_name.clone()];
+    args.extend(compile_index_options(request)?);
+
+    args.push("SCHEMA".to_string());
+    for field in &request.schema {
+        args.extend(compile_schema_field(field)?);
+    }
+
+    Ok(args)
+}
+
+/// The FT.CREATE arguments between the index name and SCHEMA.
+pub fn compile_index_options(request: &IndexRequest) -> Result<Vec<String>, ValidationError> {
+    let mut args = vec![
+        "ON".to_string(),
+        request.index_type.clone(),
+        "PREFIX".to_string(),
+        request.prefixes.len().to_string(),
+    ];
+    args.extend(request.prefixes.iter().cloned());
+
+    if let Some(filter) = &request.filter {
+        args.push("FILTER".to_string());
+        args.push(filter.clone());
+    }
+
+    // Specify language if present
+    if let Some(language) = &request.language {
+        args.push("LANGUAGE".to_string());
+        args.push(language.clone());
+    }
+
+    if let Some(language_field) = &request.language_field {
+        args.push("LANGUAGE_FIELD".to_string());
+        args.push(language_field.clone());
+    }
+    if let Some(score) = request.score {
+        if !(0.0..=1.0).contains(&score) {
+            return Err(ValidationError::new("Index score must be between 0 and 1"));
+        }
+        args.push("SCORE".to_string());
+        args.push(score.to_string());
+    }
+    if let Some(score_field) = &request.score_field {
+        args.push("SCORE_FIELD".to_string());
+        args.push(score_field.clone());
+    }
+    if let Some(payload_field) = &request.payload_field {
+        args.push("PAYLOAD_FIELD".to_string());
+        args.push(payload_field.clone());
+    }
+    if request.max_text_fields {
+        args.push("MAXTEXTFIELDS".to_string());
+    }
+    if let Some(temporary) = request.temporary {
+        args.push("TEMPORARY".to_string());
+        args.push(temporary.to_string());
+    }
+
+    let flags = [
+        (request.no_offsets, "NOOFFSETS"),
+        (request.no_highlight, "NOHL"),
+        (request.no_fields, "NOFIELDS"),
+        (request.no_freqs, "NOFREQS"),
+    ];
+    args.extend(
+        flags
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, flag)| flag.to_string()),
+    );
+
+    if let Some(stopwords) = &request.stopwords {
+        args.push("STOPWORDS".to_string());
+        args.push(stopwords.len().to_string());
+        args.extend(stopwords.iter().cloned());
+    }
+    if request.skip_initial_scan {
+        args.push("SKIPINITIALSCAN".to_string());
+    }
+
+    Ok(args)
+}
+
+pub fn compile_schema_field(field: &SchemaField) -> Result<Vec<String>, ValidationError> {
+    // `$.path as alias` is passed through as separate arguments
+    let mut args: Vec<String> = field
+        .field_name
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    if args.is_empty() {
+        return Err(ValidationError::new("Schema field_name is missing"));
+    }
+
+    let field_type = field.field_type.to_uppercase();
+    args.push(field_type.clone());
+
+    if field_type == "VECTOR" {
+        let vector = field.vector.as_ref().ok_or_else(|| {
+            ValidationError::new(format!(
+                "VECTOR field '{}' requires vector options",
+                field.field_name
+            ))
+        })?;
+        args.extend(compile_vector_options(vector)?);
+    } else if field_type == "GEOSHAPE" {
+        if let Some(coord_system) = &field.coord_system {
+            let coord_system = coord_system.to_uppercase();
+            if !COORD_SYSTEMS.contains(&coord_system.as_str()) {
+                return Err(ValidationError::new(format!(
+                    "GEOSHAPE coord_system must be one of {}",
+                    COORD_SYSTEMS.join(", ")
+                )));
+            }
+            args.push(coord_system);
+        }
+    } else {
+        args.extend(compile_field_options(field, &field_type)?);
+    }
+
+    Ok(args)
+}
+
+/// Type-specific options, then SORTABLE [UNF] and NOINDEX, in the order FT.CREATE expects.
+fn compile_field_options(
+    field: &SchemaField,
+    field_type: &str,
+) -> Result<Vec<String>, ValidationError> {
+    let is_text = field_type == "TEXT";
+    let is_tag = field_type == "TAG";
+    let unsupported = |option: &str| {
+        ValidationError::new(format!(
+            "Option '{}' is not supported on {} field '{}'",
+            option, field_type, field.field_name
+        ))
+    };
+
+    let mut args = Vec::new();
+
+    if let Some(weight) = field.weight {
+        if !is_text {
+            return Err(unsupported("weight"));
+        }
+        if weight <= 0.0 {
+            return Err(ValidationError::new(format!(
+                "Weight of '{}' must be positive",
+                field.field_name
+            )));
+        }
+        args.push("WEIGHT".to_string());
+        args.push(weight.to_string());
+    }
+    if field.nostem {
+        if !is_text {
+            return Err(unsupported("nostem"));
+        }
+        args.push("NOSTEM".to_string());
+    }
+    if let Some(phonetic) = &field.phonetic {
+        if !is_text {
+            return Err(unsupported("phonetic"));
+        }
+        let phonetic = phonetic.to_lowercase();
+        if !PHONETIC_MATCHERS.contains(&phonetic.as_str()) {
+            return Err(ValidationError::new(format!(
+                "Phonetic matcher must be one of {}",
+                PHONETIC_MATCHERS.join(", ")
+            )));
+        }
+        args.push("PHONETIC".to_string());
+        args.push(phonetic);
+    }
+
+    if let Some(separator) = &field.separator {
+        if !is_tag {
+            return Err(unsupported("separator"));
+        }
+        if separator.chars().count() != 1 {
+            return Err(ValidationError::new(format!(
+                "Separator of '{}' must be a single character",
+                field.field_name
+            )));
+        }
+        args.push("SEPARATOR".to_string());
+        args.push(separator.clone());
+    }
+    if field.casesensitive {
+        if !is_tag {
+            return Err(unsupported("casesensitive"));
+        }
+        args.push("CASESENSITIVE".to_string());
+    }
+
+    if field.withsuffixtrie {
+        if !is_text && !is_tag {
+            return Err(unsupported("withsuffixtrie"));
+        }
+        args.push("WITHSUFFIXTRIE".to_string());
+    }
+    if field.index_empty {
+        if !is_text && !is_tag {
+            return Err(unsupported("index_empty"));
+        }
+        args.push("INDEXEMPTY".to_string());
+    }
+    if field.index_missing {
+        args.push("INDEXMISSING".to_string());
+    }
+
+    if field.sortable {
+        args.push("SORTABLE".to_string());
+        if field.unf {
+            args.push("UNF".to_string());
+        }
+    } else if field.unf {
+        return Err(ValidationError::new(format!(
+            "Option 'unf' on '{}' requires 'sortable'",
+            field.field_name
+        )));
+    }
+    if field.noindex {
+        args.push("NOINDEX".to_string());
+    }
+
+    Ok(args)
+}
+
+fn compile_vector_options(vector: &VectorOptions) -> Result<Vec<String>, ValidationError> {
+    let algorithm = vector.algorithm.to_uppercase();
+    if !VECTOR_ALGORITHMS.contains(&algorithm.as_str()) {
+        return Err(ValidationError::new(format!(
+            "Vector algorithm must be one of {}",
+            VECTOR_ALGORITHMS.join(", ")
+        )));
+    }
+
+    let distance_metric = vector.distance_metric.to_uppercase();
+    if !DISTANCE_METRICS.contains(&distance_metric.as_str()) {
+        return Err(ValidationError::new(format!(
+            "Vector distance_metric must be one of {}",
+            DISTANCE_METRICS.join(", ")
+        )));
+    }
+
+    let data_type = vector_data_type(vector.data_type.as_deref())?;
+    if vector.dim == 0 {
+        return Err(ValidationError::new("Vector dim must be positive"));
+    }
+
+    let mut attributes = vec![
+        "TYPE".to_string(),
+        data_type.to_string(),
+        "DIM".to_string(),
+        vector.dim.to_string(),
+        "DISTANCE_METRIC".to_string(),
+        distance_metric,
+    ];
+
+    let mut optional = vec![("INITIAL_CAP", vector.initial_cap)];
+    if algorithm == "FLAT" {
+        optional.push(("BLOCK_SIZE", vector.block_size));
+    } else {
+        optional.push(("M", vector.m));
+        optional.push(("EF_CONSTRUCTION", vector.ef_construction));
+        optional.push(("EF_RUNTIME", vector.ef_runtime));
+    }
+    for (name, value) in optional {
+        if let Some(value) = value {
+            attributes.push(name.to_string());
+            attributes.push(value.to_string());
+        }
+    }
+
+    let mut args = vec![algorithm, attributes.len().to_string()];
+    args.extend(attributes);
+    Ok(args)
+}
+
+pub fn vector_data_type(data_type: Option<&str>) -> Result<&'static str, ValidationError> {
+    match data_type.map(str::to_uppercase).as_deref() {
+        None | Some("FLOAT32") => Ok("FLOAT32"),
+        Some("FLOAT64") => Ok("FLOAT64"),
+        Some(_) => Err(ValidationError::new(format!(
+            "Vector data_type must be one of {}",
+            VECTOR_DATA_TYPES.join(", ")
+        ))),
+    }
+}
+
+/// JSONPath of a schema field, the part of `field_name` before any `as <attribute>`.
+pub fn field_path(field: &SchemaField) -> &str {
+    field
+        .field_name
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+}
+
+/// Reads a simple dotted JSONPath (`$.a.b`, or `a.b`) from a document.
+pub fn get_json_path<'a>(document: &'a Value, path: &str) -> Option<&'a Value> {
+    path_segments(path).try_fold(document, |value, segment| value.get(segment))
+}
+
+/// Whether a JSONPath only uses dotted member access, the form `get_json_path` can read.
+/// Wildcards, recursive descent, brackets and filters are not.
+pub fn is_dotted_path(path: &str) -> bool {
+    !path.contains("..") && !path.contains(['[', ']', '*', '?', '(', ')', '@'])
+}
+
+/// Writes a simple dotted JSONPath into a document, creating intermediate objects.
+pub fn set_json_path(document: &mut Value, path: &str, new_value: Value) {
+    let segments: Vec<&str> = path_segments(path).collect();
+    let mut current = document;
+    for (i, segment) in segments.iter().enumerate() {
+        let object = match current {
+            Value::Object(object) => object,
+            _ => return,
+        };
+        if i == segments.len() - 1 {
+            object.insert(segment.to_string(), new_value);
+            return;
+        }
+        current = object
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.trim_start_matches('$')
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+}
+
+/// Whether two prefix lists can match the same key. An empty prefix matches every key.
+pub fn prefixes_overlap(a: &[String], b: &[String]) -> bool {
+    a.iter().any(|a| {
+        b.iter()
+            .any(|b| a.starts_with(b.as_str()) || b.starts_with(a.as_str()))
+    })
+}
+
+/// Identifier (`$.path` or hash field) and attribute name of a schema field.
+pub fn field_names(field: &SchemaField) -> (&str, &str) {
+    let tokens: Vec<&str> = field.field_name.split_whitespace().collect();
+    match tokens.as_slice() {
+        [identifier, as_keyword, attribute, ..] if as_keyword.eq_ignore_ascii_case("as") => {
+            (identifier, attribute)
+        }
+        [identifier, ..] => (identifier, identifier),
+        [] => ("", ""),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    /// No index exists yet.
+    Create,
+    /// Nothing FT.CREATE cares about changed.
+    None,
+    /// Only new fields, added with FT.ALTER SCHEMA ADD.
+    Alter,
+    /// Incompatible changes, a new index version is built.
+    Rebuild,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchemaChange {
+    pub field: String,
+    /// `add`, `remove` or `modify`.
+    pub change: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// How `/index` brings the live index in line with a requested definition.
+#[derive(Debug, Serialize)]
+pub struct SchemaPlan {
+    pub action: PlanAction,
+    pub changes: Vec<SchemaChange>,
+    #[serde(skip)]
+    pub added_fields: Vec<SchemaField>,
+}
+
+impl SchemaPlan {
+    pub fn create() -> Self {
+        SchemaPlan {
+            action: PlanAction::Create,
+            changes: Vec::new(),
+            added_fields: Vec::new(),
+        }
+    }
+}
+
+/// Compares a requested definition with the live index. Fields are matched by attribute name
+/// against FT.INFO; options FT.INFO does not report (vector parameters, phonetic matchers,
+/// index-level settings) are compared with the definition stored by the previous `/index`.
+pub fn diff_schema(
+    request: &IndexRequest,
+    live: &IndexInfo,
+    stored: Option<&IndexRequest>,
+) -> Result<SchemaPlan, ValidationError> {
+    let mut changes = Vec::new();
+    let mut rebuild = false;
+    let modified = |field: &str, detail: String| SchemaChange {
+        field: field.to_string(),
+        change: "modify",
+        detail: Some(detail),
+    };
+
+    let definition = &live.index_definition;
+    if !definition
+        .key_type
+        .eq_ignore_ascii_case(&request.index_type)
+    {
+        changes.push(modified(
+            "type",
+            format!("{} -> {}", definition.key_type, request.index_type),
+        ));
+        rebuild = true;
+    }
+    if definition.prefixes != request.prefixes {
+        changes.push(modified(
+            "prefixes",
+            format!("{:?} -> {:?}", definition.prefixes, request.prefixes),
+        ));
+        rebuild = true;
+    }
+    if let Some(stored) = stored {
+        if compile_index_options(stored)? != compile_index_options(request)? {
+            changes.push(modified(
+                "index options",
+                "FT.CREATE options changed".to_string(),
+            ));
+            rebuild = true;
+        }
+    }
+
+    let mut added_fields = Vec::new();
+    for field in &request.schema {
+        let (_, attribute) = field_names(field);
+        let live_attribute = live
+            .attributes
+            .iter()
+            .find(|live_attribute| live_attribute.attribute == attribute);
+        let Some(live_attribute) = live_attribute else {
+            changes.push(SchemaChange {
+                field: attribute.to_string(),
+                change: "add",
+                detail: None,
+            });
+            added_fields.push(field.clone());
+            continue;
+        };
+
+        let stored_field =
+            stored.and_then(|stored| stored.schema.iter().find(|f| field_names(f).1 == attribute));
+        let mut differences = live_field_differences(field, live_attribute);
+        if let Some(stored_field) = stored_field {
+            if differences.is_empty()
+                && compile_schema_field(stored_field)? != compile_schema_field(field)?
+            {
+                differences.push("options".to_string());
+            }
+        }
+        if !differences.is_empty() {
+            changes.push(modified(attribute, differences.join(", ")));
+            rebuild = true;
+        }
+    }
+
+    for live_attribute in &live.attributes {
+        if !request
+            .schema
+            .iter()
+            .any(|field| field_names(field).1 == live_attribute.attribute)
+        {
+            changes.push(SchemaChange {
+                field: live_attribute.attribute.clone(),
+                change: "remove",
+                detail: None,
+            });
+            rebuild = true;
+        }
+    }
+
+    let action = if rebuild {
+        PlanAction::Rebuild
+    } else if !added_fields.is_empty() {
+        PlanAction::Alter
+    } else {
+        PlanAction::None
+    };
+    Ok(SchemaPlan {
+        action,
+        changes,
+        added_fields,
+    })
+}
+
+/// Differences between a requested field and its FT.INFO description.
+fn live_field_differences(field: &SchemaField, live: &AttributeInfo) -> Vec<String> {
+    let (identifier, _) = field_names(field);
+    let field_type = field.field_type.to_uppercase();
+    let mut differences = Vec::new();
+
+    if live.identifier != identifier {
+        differences.push(format!("identifier {} -> {}", live.identifier, identifier));
+    }
+    let live_type = &live.field_type;
+    if !live_type.eq_ignore_ascii_case(&field_type) {
+        differences.push(format!("type {} -> {}", live_type, field_type));
+        return differences;
+    }
+    // Vector and shape parameters are reported in their own formats
+    if field_type == "VECTOR" || field_type == "GEOSHAPE" {
+        return differences;
+    }
+
+    let is_text_or_tag = field_type == "TEXT" || field_type == "TAG";
+    let flags = [
+        ("SORTABLE", field.sortable),
+        ("UNF", field.unf && is_text_or_tag),
+        ("NOSTEM", field.nostem),
+        ("NOINDEX", field.noindex),
+        ("CASESENSITIVE", field.casesensitive),
+        ("WITHSUFFIXTRIE", field.withsuffixtrie),
+        ("INDEXEMPTY", field.index_empty),
+        ("INDEXMISSING", field.index_missing),
+    ];
+    for (flag, wanted) in flags {
+        if flag == "UNF" && !is_text_or_tag {
+            continue;
+        }
+        if live.has_flag(flag) != wanted {
+            differences.push(format!("{} {}", flag, if wanted { "on" } else { "off" }));
+        }
+    }
+
+    if field_type == "TEXT" {
+        let live_weight = live.weight.unwrap_or(1.0);
+        let weight = field.weight.unwrap_or(1.0);
+        if (live_weight - weight).abs() > f64::EPSILON {
+            differences.push(format!("WEIGHT {} -> {}", live_weight, weight));
+        }
+    }
+    if field_type == "TAG" {
+        let live_separator = live.separator.as_deref().unwrap_or(",");
+        let separator = field.separator.as_deref().unwrap_or(",");
+        if live_separator != separator {
+            differences.push(format!("SEPARATOR {} -> {}", live_separator, separator));
+        }
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::index_info::IndexDefinitionInfo;
+    use serde_json::json;
+
+    fn request() -> IndexRequest {
+        serde_json::from_value(json!({
+            "index_name": "posts",
+            "type": "JSON",
+            "prefixes": ["post:"],
+            "schema": [
+                { "field_name": "$.title as title", "field_type": "TEXT", "weight": 2.0 },
+                { "field_name": "$.ts as ts", "field_type": "NUMERIC", "sortable": true },
+                { "field_name": "$.channel as channel", "field_type": "TAG" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn attribute(identifier: &str, attribute: &str, field_type: &str) -> AttributeInfo {
+        AttributeInfo {
+            identifier: identifier.to_string(),
+            attribute: attribute.to_string(),
+            field_type: field_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn live() -> IndexInfo {
+        IndexInfo {
+            index_name: "posts_v1".to_string(),
+            index_definition: IndexDefinitionInfo {
+                key_type: "JSON".to_string(),
+                prefixes: vec!["post:".to_string()],
+                ..Default::default()
+            },
+            attributes: vec![
+                AttributeInfo {
+                    weight: Some(2.0),
+                    ..attribute("$.title", "title", "TEXT")
+                },
+                AttributeInfo {
+                    flags: vec!["SORTABLE".to_string()],
+                    ..attribute("$.ts", "ts", "NUMERIC")
+                },
+                AttributeInfo {
+                    separator: Some(",".to_string()),
+                    ..attribute("$.channel", "channel", "TAG")
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn changes(plan: &SchemaPlan) -> Vec<(&str, &str, Option<&str>)> {
+        plan.changes
+            .iter()
+            .map(|c| (c.field.as_str(), c.change, c.detail.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn dotted_paths_are_recognized() {
+        for path in ["$.title", "$.meta.ts", "title", "$"] {
+            assert!(is_dotted_path(path), "{}", path);
+        }
+        for path in [
+            "$..title",
+            "$.tags[0]",
+            "$.tags.*",
+            "$.a[?(@.b > 1)]",
+            "$['title']",
+        ] {
+            assert!(!is_dotted_path(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn unchanged_definition_plans_nothing() {
+        let request = request();
+        let plan = diff_schema(&request, &live(), Some(&request)).unwrap();
+        assert_eq!(plan.action, PlanAction::None);
+        assert!(plan.changes.is_empty());
+    }
+
+    #[test]
+    fn new_fields_are_altered_in() {
+        let mut request = request();
+        request.schema.push(
+            serde_json::from_value(
+                json!({ "field_name": "$.author as author", "field_type": "TAG" }),
+            )
+            .unwrap(),
+        );
+
+        let plan = diff_schema(&request, &live(), None).unwrap();
+        assert_eq!(plan.action, PlanAction::Alter);
+        assert_eq!(changes(&plan), [("author", "add", None)]);
+        assert_eq!(plan.added_fields.len(), 1);
+        assert_eq!(plan.added_fields[0].field_name, "$.author as author");
+    }
+
+    #[test]
+    fn modified_and_removed_fields_need_a_rebuild() {
+        let mut request = request();
+        request.schema[0].weight = Some(3.0);
+        request.schema[1].sortable = false;
+        request.schema.remove(2);
+
+        let plan = diff_schema(&request, &live(), None).unwrap();
+        assert_eq!(plan.action, PlanAction::Rebuild);
+        assert_eq!(
+            changes(&plan),
+            [
+                ("title", "modify", Some("WEIGHT 2 -> 3")),
+                ("ts", "modify", Some("SORTABLE off")),
+                ("channel", "remove", None),
+            ]
+        );
+        assert!(plan.added_fields.is_empty());
+    }
+
+    #[test]
+    fn definition_changes_need_a_rebuild() {
+        let mut request = request();
+        request.prefixes = vec!["posts:".to_string()];
+        request.schema[2].field_type = "TEXT".to_string();
+
+        let plan = diff_schema(&request, &live(), None).unwrap();
+        assert_eq!(plan.action, PlanAction::Rebuild);
+        assert_eq!(
+            changes(&plan),
+            [
+                ("prefixes", "modify", Some("[\"post:\"] -> [\"posts:\"]")),
+                ("channel", "modify", Some("type TAG -> TEXT")),
+            ]
+        );
+    }
+
+    #[test]
+    fn options_missing_from_ft_info_are_compared_with_the_stored_definition() {
+        let stored = request();
+        let mut request = request();
+        request.stopwords = Some(Vec::new());
+        request.schema[0].phonetic = Some("dm:en".to_string());
+
+        let plan = diff_schema(&request, &live(), Some(&stored)).unwrap();
+        assert_eq!(plan.action, PlanAction::Rebuild);
+        assert_eq!(
+            changes(&plan),
+            [
+                ("index options", "modify", Some("FT.CREATE options changed")),
+                ("title", "modify", Some("options")),
+            ]
+        );
+    }
+
+    #[test]
+    fn live_field_differences_reports_flags_and_identifier() {
+        let mut field = request().schema.remove(2);
+        field.field_name = "$.tags as channel".to_string();
+        field.casesensitive = true;
+        field.separator = Some(";".to_string());
+
+        let live = AttributeInfo {
+            separator: Some(",".to_string()),
+            flags: vec!["SORTABLE".to_string()],
+            ..attribute("$.channel", "channel", "TAG")
+        };
+        assert_eq!(
+            live_field_differences(&field, &live),
+            [
+                "identifier $.channel -> $.tags",
+                "SORTABLE off",
+                "CASESENSITIVE on",
+                "SEPARATOR , -> ;",
+            ]
+        );
+    }
+}
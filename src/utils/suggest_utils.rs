@@ -0,0 +1,82 @@
+use crate::models::index_request::SuggestSource;
+use crate::models::validation_error::ValidationError;
+use crate::utils::query_utils::validate_field_name;
+use crate::utils::redis_utils::{parse_number, value_to_string};
+use crate::utils::schema_utils::get_json_path;
+use mobc_redis::redis;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+/// Key of the FT.SUGADD dictionary `dictionary` of `index`.
+pub fn suggestion_key(index: &str, dictionary: &str) -> Result<String, ValidationError> {
+    if index.trim().is_empty() {
+        return Err(ValidationError::new("The 'index' field is required."));
+    }
+    validate_field_name(dictionary)
+        .map_err(|_| ValidationError::new(format!("Invalid dictionary name '{}'", dictionary)))?;
+    Ok(format!("data-node:suggest:{}:{}", index, dictionary))
+}
+
+/// Hash counting how many documents carry each string of a dictionary.
+pub fn suggestion_refs_key(dictionary_key: &str) -> String {
+    format!("{}:refs", dictionary_key)
+}
+
+pub fn source_dictionary(source: &SuggestSource) -> String {
+    source.dictionary.clone().unwrap_or_else(|| {
+        source
+            .field
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    })
+}
+
+/// Distinct non-empty strings of a string or string array field.
+pub fn suggestion_strings(record: &Value, path: &str) -> BTreeSet<String> {
+    let values = match get_json_path(record, path) {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(value) => vec![value],
+        None => Vec::new(),
+    };
+    values
+        .into_iter()
+        .filter_map(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parses an FT.SUGGET reply, whose entries are the string followed by the score and the
+/// payload when requested.
+pub fn parse_suggestions(
+    reply: &[redis::Value],
+    with_scores: bool,
+    with_payloads: bool,
+) -> Vec<Value> {
+    let stride = 1 + with_scores as usize + with_payloads as usize;
+    reply
+        .chunks(stride)
+        .filter_map(|entry| {
+            let mut suggestion = json!({"string": value_to_string(entry.first()?)?});
+            let mut rest = entry.iter().skip(1);
+            if with_scores {
+                suggestion["score"] = rest
+                    .next()
+                    .and_then(value_to_string)
+                    .and_then(|score| parse_number(&score))
+                    .unwrap_or(Value::Null);
+            }
+            if with_payloads {
+                suggestion["payload"] = rest
+                    .next()
+                    .and_then(value_to_string)
+                    .map(Value::String)
+                    .unwrap_or(Value::Null);
+            }
+            Some(suggestion)
+        })
+        .collect()
+}
@@ -0,0 +1,218 @@
+use crate::models::index_request::{IndexRequest, SchemaField};
+use crate::utils::schema_utils::{field_names, field_path, get_json_path, is_dotted_path};
+use serde::Serialize;
+use serde_json::Value;
+
+/// A field of a document that the index it falls under would fail to index.
+#[derive(Debug, Serialize)]
+pub struct RecordError {
+    pub index: String,
+    pub field: String,
+    pub error: String,
+}
+
+/// Checks a document against the schema of every JSON index whose prefixes cover its key.
+/// Missing fields are accepted unless declared `required`; present ones must have a value
+/// RediSearch can index for the field type. Only dotted paths are checked.
+pub fn validate_record(
+    definitions: &[IndexRequest],
+    key: &str,
+    record: &Value,
+) -> Vec<RecordError> {
+    let mut errors = Vec::new();
+    for definition in definitions {
+        if !definition.index_type.eq_ignore_ascii_case("JSON")
+            || !definition
+                .prefixes
+                .iter()
+                .any(|p| key.starts_with(p.as_str()))
+        {
+            continue;
+        }
+
+        for field in &definition.schema {
+            // Other JSONPath forms can't be resolved here and are left to RediSearch
+            if !is_dotted_path(field_path(field)) {
+                continue;
+            }
+            let error = match get_json_path(record, field_path(field)) {
+                None | Some(Value::Null) if field.required => Some("is required".to_string()),
+                None | Some(Value::Null) => None,
+                Some(value) => value_error(field, value),
+            };
+            if let Some(error) = error {
+                errors.push(RecordError {
+                    index: definition.index_name.clone(),
+                    field: field_names(field).1.to_string(),
+                    error,
+                });
+            }
+        }
+    }
+    errors
+}
+
+/// Describes why `value` cannot be indexed as the field's type, if it cannot.
+fn value_error(field: &SchemaField, value: &Value) -> Option<String> {
+    let field_type = field.field_type.to_uppercase();
+    if field_type == "VECTOR" {
+        let dim = field.vector.as_ref().map(|vector| vector.dim as usize);
+        return match value.as_array() {
+            Some(items) if items.iter().all(Value::is_number) => match dim {
+                Some(dim) if items.len() != dim => Some(format!(
+                    "expected a vector of {} numbers, got {}",
+                    dim,
+                    items.len()
+                )),
+                _ => None,
+            },
+            _ => Some(format!(
+                "expected an array of numbers, got {}",
+                json_type(value)
+            )),
+        };
+    }
+
+    // Apart from vectors, every type also accepts an array of its values
+    let values = match value {
+        Value::Array(items) => items.iter().collect(),
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .find_map(|value| match field_type.as_str() {
+            "TEXT" if !value.is_string() => {
+                Some(format!("expected a string, got {}", json_type(value)))
+            }
+            "NUMERIC" if !value.is_number() => {
+                Some(format!("expected a number, got {}", json_type(value)))
+            }
+            "TAG" if !(value.is_string() || value.is_boolean()) => Some(format!(
+                "expected a string or boolean, got {}",
+                json_type(value)
+            )),
+            "GEO" if !value.as_str().is_some_and(is_geo_point) => {
+                Some(format!("expected a \"lon,lat\" string, got {}", value))
+            }
+            "GEOSHAPE" if !value.is_string() => {
+                Some(format!("expected a WKT string, got {}", json_type(value)))
+            }
+            _ => None,
+        })
+}
+
+fn is_geo_point(point: &str) -> bool {
+    let coordinates: Vec<Option<f64>> = point
+        .split(',')
+        .map(|coordinate| coordinate.trim().parse::<f64>().ok())
+        .collect();
+    match coordinates.as_slice() {
+        [Some(lon), Some(lat)] => {
+            (-180.0..=180.0).contains(lon) && (-85.05112878..=85.05112878).contains(lat)
+        }
+        _ => false,
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definitions() -> Vec<IndexRequest> {
+        vec![
+            serde_json::from_value(json!({
+                "index_name": "posts",
+                "type": "JSON",
+                "prefixes": ["post:"],
+                "schema": [
+                    { "field_name": "$.title as title", "field_type": "TEXT" },
+                    { "field_name": "$.meta.ts as ts", "field_type": "NUMERIC", "required": true },
+                    { "field_name": "$.tags as tags", "field_type": "TAG" },
+                    { "field_name": "$.location as location", "field_type": "GEO" },
+                    { "field_name": "$.comments[*].text as comments", "field_type": "TEXT" },
+                    {
+                        "field_name": "$.embedding as embedding",
+                        "field_type": "VECTOR",
+                        "vector": { "algorithm": "FLAT", "dim": 3, "distance_metric": "COSINE" }
+                    }
+                ]
+            }))
+            .unwrap(),
+            serde_json::from_value(json!({
+                "index_name": "hashes",
+                "type": "HASH",
+                "prefixes": ["post:"],
+                "schema": [{ "field_name": "title", "field_type": "NUMERIC" }]
+            }))
+            .unwrap(),
+        ]
+    }
+
+    fn errors(key: &str, record: Value) -> Vec<(String, String)> {
+        validate_record(&definitions(), key, &record)
+            .into_iter()
+            .map(|e| (e.field, e.error))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_valid_record() {
+        let record = json!({
+            "title": "Hello",
+            "meta": { "ts": 1700000000 },
+            "tags": ["news", true],
+            "location": "-73.98,40.75",
+            "comments": [{ "text": 1 }],
+            "embedding": [0.1, 0.2, 0.3]
+        });
+        assert!(errors("post:1", record).is_empty());
+    }
+
+    #[test]
+    fn requires_declared_fields_only() {
+        assert_eq!(
+            errors("post:1", json!({ "meta": { "ts": null } })),
+            [("ts".to_string(), "is required".to_string())]
+        );
+        assert!(errors("post:1", json!({ "meta": { "ts": 1 } })).is_empty());
+    }
+
+    #[test]
+    fn reports_values_of_the_wrong_type() {
+        let record = json!({
+            "title": ["ok", 3],
+            "meta": { "ts": "yesterday" },
+            "tags": [{ "name": "news" }],
+            "location": "200,40",
+            "embedding": [0.1, 0.2]
+        });
+        assert_eq!(
+            errors("post:1", record),
+            [
+                ("title", "expected a string, got number"),
+                ("ts", "expected a number, got string"),
+                ("tags", "expected a string or boolean, got object"),
+                ("location", "expected a \"lon,lat\" string, got \"200,40\""),
+                ("embedding", "expected a vector of 3 numbers, got 2"),
+            ]
+            .map(|(field, error)| (field.to_string(), error.to_string()))
+        );
+    }
+
+    #[test]
+    fn skips_keys_outside_the_prefixes() {
+        assert!(errors("user:1", json!({ "title": 1 })).is_empty());
+    }
+}